@@ -1,24 +1,32 @@
 use crate::{
+    execute::{CodeExecuter, ExecutionHandle, ExecutionState, ProcessStatus},
     markdown::{
         elements::{
-            Code, ListItem, ListItemType, MarkdownElement, ParagraphElement, StyledText, Table, TableRow, Text,
+            AlertType, Code, ColumnAlignment, Highlight, HighlightGroup, ListItem, ListItemType, MarkdownElement,
+            ParagraphElement, ProgrammingLanguage, StyledText, Table, TableRow, Text,
         },
         text::{WeightedLine, WeightedText},
     },
     presentation::{
-        AsRenderOperations, PreformattedLine, Presentation, PresentationMetadata, PresentationThemeMetadata,
-        RenderOperation, Slide,
+        AsRenderOperations, PreformattedLine, Presentation, PresentationClock, PresentationMetadata,
+        PresentationOptions, PresentationThemeMetadata, RenderOnDemand, RenderOnDemandState, RenderOperation, Slide,
     },
     render::{
         highlighting::{CodeHighlighter, CodeLine},
+        layout::Layout,
         properties::WindowSize,
     },
     resource::{LoadImageError, Resources},
     style::TextStyle,
-    theme::{Alignment, AuthorPositioning, Colors, ElementType, FooterStyle, LoadThemeError, PresentationTheme},
+    theme::{
+        Alignment, AuthorPositioning, Colors, ElementType, FooterStyle, LinkMode, LoadThemeError, PresentationTheme,
+        TableBorders, TableStyle,
+    },
 };
-use std::{borrow::Cow, cell::RefCell, iter, mem, path::PathBuf, rc::Rc, str::FromStr};
-use unicode_width::UnicodeWidthStr;
+use chrono::Local;
+use crossterm::style::Color;
+use std::{borrow::Cow, cell::RefCell, collections::HashMap, mem, path::PathBuf, rc::Rc, str::FromStr, time::Duration};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
 /// Builds a presentation.
 ///
@@ -33,6 +41,14 @@ pub struct PresentationBuilder<'a> {
     ignore_element_line_break: bool,
     last_element_is_list: bool,
     footer_context: Rc<RefCell<FooterContext>>,
+    slide_notes: Vec<String>,
+    slide_after_pause: bool,
+    layout: LayoutState,
+    options: PresentationOptions,
+    footnotes: HashMap<String, Footnote>,
+    slide_footnotes: Vec<String>,
+    nested_alignment: Option<Alignment>,
+    variables: HashMap<String, String>,
 }
 
 impl<'a> PresentationBuilder<'a> {
@@ -51,6 +67,14 @@ impl<'a> PresentationBuilder<'a> {
             ignore_element_line_break: false,
             last_element_is_list: false,
             footer_context: Default::default(),
+            slide_notes: Vec::new(),
+            slide_after_pause: false,
+            layout: Default::default(),
+            options: Default::default(),
+            footnotes: HashMap::new(),
+            slide_footnotes: Vec::new(),
+            nested_alignment: None,
+            variables: HashMap::new(),
         }
     }
 
@@ -60,6 +84,7 @@ impl<'a> PresentationBuilder<'a> {
             self.process_front_matter(contents)?;
         }
         self.set_code_theme()?;
+        self.collect_footnotes(&elements);
 
         if self.slide_operations.is_empty() {
             self.push_slide_prelude();
@@ -72,37 +97,67 @@ impl<'a> PresentationBuilder<'a> {
             }
         }
         if !self.slide_operations.is_empty() {
-            self.terminate_slide();
+            self.terminate_slide()?;
         }
         self.footer_context.borrow_mut().total_slides = self.slides.len();
 
-        let presentation = Presentation::new(self.slides);
+        let clock = self.footer_context.borrow().clock.clone();
+        let presentation = Presentation::new(self.slides).with_clock(clock);
         Ok(presentation)
     }
 
+    fn collect_footnotes(&mut self, elements: &[MarkdownElement]) {
+        // Definitions are sorted by the position of their first reference so this is the order
+        // they're numbered in.
+        let definitions = elements.iter().filter_map(|element| match element {
+            MarkdownElement::FootnoteDefinition { name, text } => Some((name, text)),
+            _ => None,
+        });
+        for (index, (name, text)) in definitions.enumerate() {
+            self.footnotes.insert(name.clone(), Footnote { number: index + 1, text: text.clone() });
+        }
+    }
+
+    // Blocks within list items are aligned with the item's text rather than using their own
+    // alignment.
+    fn alignment(&self, element: &ElementType) -> Alignment {
+        self.nested_alignment.clone().unwrap_or_else(|| self.theme.alignment(element))
+    }
+
     fn push_slide_prelude(&mut self) {
         let colors = self.theme.default_style.colors.clone();
         self.slide_operations.push(RenderOperation::SetColors(colors));
         self.slide_operations.push(RenderOperation::ClearScreen);
         self.push_line_break();
+        if self.options.jump_to_middle {
+            self.slide_operations.push(RenderOperation::JumpToMiddle);
+        }
     }
 
     fn process_element(&mut self, element: MarkdownElement) -> Result<(), BuildError> {
         let is_list = matches!(element, MarkdownElement::List(_));
+        let is_hidden = matches!(element, MarkdownElement::Comment(_) | MarkdownElement::FootnoteDefinition { .. });
+        if matches!(self.layout, LayoutState::InLayout { .. }) && !is_hidden {
+            return Err(BuildError::InvalidLayout("elements in a column layout must be placed within a column"));
+        }
         match element {
             // This one is processed before everything else as it affects how the rest of the
             // elements is rendered.
             MarkdownElement::FrontMatter(_) => self.ignore_element_line_break = true,
-            MarkdownElement::SetexHeading { text } => self.push_slide_title(text),
-            MarkdownElement::Heading { level, text } => self.push_heading(level, text),
+            MarkdownElement::SetexHeading { text } => self.push_slide_title(text)?,
+            MarkdownElement::Heading { level, text } => self.push_heading(level, text)?,
             MarkdownElement::Paragraph(elements) => self.push_paragraph(elements)?,
-            MarkdownElement::List(elements) => self.push_list(elements),
-            MarkdownElement::Code(code) => self.push_code(code),
-            MarkdownElement::Table(table) => self.push_table(table),
+            MarkdownElement::List(elements) => self.push_list(elements)?,
+            MarkdownElement::Code(code) => self.push_code(code)?,
+            MarkdownElement::Table(table) => self.push_table(table)?,
             MarkdownElement::ThematicBreak => self.push_separator(),
-            MarkdownElement::Comment(comment) => self.process_comment(comment),
-            MarkdownElement::BlockQuote(lines) => self.push_block_quote(lines),
+            MarkdownElement::Comment(comment) => self.process_comment(comment)?,
+            MarkdownElement::BlockQuote(elements) => self.push_block_quote(elements)?,
+            MarkdownElement::Alert { alert_type, title, elements } => self.push_alert(alert_type, title, elements)?,
             MarkdownElement::Image(path) => self.push_image(path)?,
+            // These were collected before processing anything else and they're displayed at the
+            // bottom of the slides they're referenced in.
+            MarkdownElement::FootnoteDefinition { .. } => self.ignore_element_line_break = true,
         };
         self.last_element_is_list = is_list;
         Ok(())
@@ -113,10 +168,22 @@ impl<'a> PresentationBuilder<'a> {
             serde_yaml::from_str(contents).map_err(|e| BuildError::InvalidMetadata(e.to_string()))?;
 
         self.footer_context.borrow_mut().author = metadata.author.clone().unwrap_or_default();
+        self.variables = metadata.vars.clone().into_iter().collect();
+        for (name, value) in [("date", &metadata.date), ("event", &metadata.event)] {
+            if let Some(value) = value {
+                self.variables.insert(name.into(), value.clone());
+            }
+        }
+        if let Some(duration) = &metadata.duration {
+            let duration = parse_duration(duration)
+                .ok_or_else(|| BuildError::InvalidMetadata(format!("invalid duration '{duration}'")))?;
+            self.footer_context.borrow_mut().duration = Some(duration);
+        }
         self.set_theme(&metadata.theme)?;
+        self.options = metadata.options.clone();
         if metadata.title.is_some() || metadata.sub_title.is_some() || metadata.author.is_some() {
             self.push_slide_prelude();
-            self.push_intro_slide(metadata);
+            self.push_intro_slide(metadata)?;
         }
         Ok(())
     }
@@ -151,7 +218,7 @@ impl<'a> PresentationBuilder<'a> {
         Ok(())
     }
 
-    fn push_intro_slide(&mut self, metadata: PresentationMetadata) {
+    fn push_intro_slide(&mut self, metadata: PresentationMetadata) -> Result<(), BuildError> {
         let styles = &self.theme.intro_slide;
         let title = StyledText::new(
             metadata.title.unwrap_or_default().clone(),
@@ -166,10 +233,10 @@ impl<'a> PresentationBuilder<'a> {
             .as_ref()
             .map(|text| StyledText::new(text.clone(), TextStyle::default().colors(styles.author.colors.clone())));
         self.slide_operations.push(RenderOperation::JumpToVerticalCenter);
-        self.push_text(Text::from(title), ElementType::PresentationTitle);
+        self.push_text(Text::from(title), ElementType::PresentationTitle)?;
         self.push_line_break();
         if let Some(text) = sub_title {
-            self.push_text(Text::from(text), ElementType::PresentationSubTitle);
+            self.push_text(Text::from(text), ElementType::PresentationSubTitle)?;
             self.push_line_break();
         }
         if let Some(text) = author {
@@ -183,41 +250,98 @@ impl<'a> PresentationBuilder<'a> {
                     self.slide_operations.push(RenderOperation::JumpToSlideBottom);
                 }
             };
-            self.push_text(Text::from(text), ElementType::PresentationAuthor);
+            self.push_text(Text::from(text), ElementType::PresentationAuthor)?;
         }
-        self.terminate_slide();
+        self.terminate_slide()
     }
 
-    fn process_comment(&mut self, comment: String) {
-        let Ok(comment) = comment.parse::<Comment>() else {
-            return;
+    fn process_comment(&mut self, comment: String) -> Result<(), BuildError> {
+        let comment = match comment.parse::<Comment>() {
+            Ok(comment) => comment,
+            // Regular comments are simply ignored.
+            Err(CommentParseError::NotACommand) => return Ok(()),
+            Err(CommentParseError::InvalidArguments(command)) => {
+                return Err(BuildError::InvalidCommentArguments(command));
+            }
         };
         match comment {
-            Comment::Pause => self.process_pause(),
-            Comment::EndSlide => self.terminate_slide(),
+            Comment::Pause => self.process_pause()?,
+            Comment::EndSlide => self.terminate_slide()?,
+            Comment::SpeakerNote(note) => self.slide_notes.push(note),
+            Comment::ColumnLayout(columns) => self.process_column_layout(columns)?,
+            Comment::Column(column) => self.process_column(column)?,
+            Comment::ResetLayout => self.process_reset_layout(),
+            Comment::JumpToMiddle => {
+                self.slide_operations.push(RenderOperation::JumpToMiddle);
+                self.ignore_element_line_break = true;
+            }
+        }
+        Ok(())
+    }
+
+    fn process_column_layout(&mut self, columns: Vec<u8>) -> Result<(), BuildError> {
+        if columns.is_empty() || columns.contains(&0) {
+            return Err(BuildError::InvalidLayout("columns must have a width larger than zero"));
+        }
+        self.layout = LayoutState::InLayout { columns_count: columns.len() };
+        self.slide_operations.push(RenderOperation::InitColumnLayout { columns });
+        self.ignore_element_line_break = true;
+        Ok(())
+    }
+
+    fn process_column(&mut self, column: usize) -> Result<(), BuildError> {
+        let columns_count = match self.layout {
+            LayoutState::Default => return Err(BuildError::InvalidLayout("column used without a column layout")),
+            LayoutState::InLayout { columns_count } | LayoutState::InColumn { columns_count } => columns_count,
+        };
+        if column >= columns_count {
+            return Err(BuildError::InvalidLayout("column index is larger than the number of columns"));
+        }
+        self.layout = LayoutState::InColumn { columns_count };
+        self.slide_operations.push(RenderOperation::EnterColumn { column });
+        self.ignore_element_line_break = true;
+        Ok(())
+    }
+
+    fn process_reset_layout(&mut self) {
+        if !matches!(self.layout, LayoutState::Default) {
+            self.layout = LayoutState::Default;
+            self.slide_operations.push(RenderOperation::ExitLayout);
         }
+        self.ignore_element_line_break = true;
     }
 
-    fn process_pause(&mut self) {
+    fn process_pause(&mut self) -> Result<(), BuildError> {
         // Remove the last line, if any, if the previous element is a list. This allows each
         // element in a list showing up without newlines in between..
         if self.last_element_is_list && matches!(self.slide_operations.last(), Some(RenderOperation::RenderLineBreak)) {
             self.slide_operations.pop();
         }
+        self.push_pause()
+    }
 
+    fn push_pause(&mut self) -> Result<(), BuildError> {
         let next_operations = self.slide_operations.clone();
-        self.terminate_slide();
+        let next_notes = self.slide_notes.clone();
+        let next_layout = self.layout.clone();
+        let next_footnotes = self.slide_footnotes.clone();
+        self.terminate_slide()?;
+        self.slide_after_pause = true;
         self.slide_operations = next_operations;
+        self.slide_notes = next_notes;
+        self.layout = next_layout;
+        self.slide_footnotes = next_footnotes;
+        Ok(())
     }
 
-    fn push_slide_title(&mut self, mut text: Text) {
+    fn push_slide_title(&mut self, mut text: Text) -> Result<(), BuildError> {
         let style = self.theme.slide_title.clone();
         text.apply_style(&TextStyle::default().bold().colors(style.colors.clone()));
 
         for _ in 0..style.padding_top.unwrap_or(0) {
             self.push_line_break();
         }
-        self.push_text(text, ElementType::SlideTitle);
+        self.push_text(text, ElementType::SlideTitle)?;
         self.push_line_break();
 
         for _ in 0..style.padding_bottom.unwrap_or(0) {
@@ -228,9 +352,10 @@ impl<'a> PresentationBuilder<'a> {
         }
         self.push_line_break();
         self.ignore_element_line_break = true;
+        Ok(())
     }
 
-    fn push_heading(&mut self, level: u8, mut text: Text) {
+    fn push_heading(&mut self, level: u8, mut text: Text) -> Result<(), BuildError> {
         let (element_type, style) = match level {
             1 => (ElementType::Heading1, &self.theme.headings.h1),
             2 => (ElementType::Heading2, &self.theme.headings.h2),
@@ -248,15 +373,16 @@ impl<'a> PresentationBuilder<'a> {
         let text_style = TextStyle::default().bold().colors(style.colors.clone());
         text.apply_style(&text_style);
 
-        self.push_text(text, element_type);
+        self.push_text(text, element_type)?;
         self.push_line_break();
+        Ok(())
     }
 
     fn push_paragraph(&mut self, elements: Vec<ParagraphElement>) -> Result<(), BuildError> {
         for element in elements {
             match element {
                 ParagraphElement::Text(text) => {
-                    self.push_text(text, ElementType::Paragraph);
+                    self.push_text(text, ElementType::Paragraph)?;
                     self.push_line_break();
                 }
                 ParagraphElement::LineBreak => {
@@ -278,84 +404,325 @@ impl<'a> PresentationBuilder<'a> {
         Ok(())
     }
 
-    fn push_list(&mut self, items: Vec<ListItem>) {
+    fn push_list(&mut self, items: Vec<ListItem>) -> Result<(), BuildError> {
+        // The indentation of the text of the last item seen in every depth.
+        let mut indentations = Vec::new();
         for item in items {
-            self.push_list_item(item);
+            self.push_list_item(item, &mut indentations)?;
+        }
+        Ok(())
+    }
+
+    fn push_list_item(&mut self, item: ListItem, indentations: &mut Vec<usize>) -> Result<(), BuildError> {
+        let (prefix, marker) = self.list_item_marker(&item, indentations);
+        let indentation = prefix.width() + marker.text.width() + 1;
+        indentations.resize(item.depth as usize + 1, 0);
+        indentations[item.depth as usize] = indentation;
+
+        let mut text = item.contents;
+        if !text.chunks.is_empty() || item.item_type != ListItemType::Continuation {
+            text.chunks.splice(0..0, [StyledText::from(prefix), marker, StyledText::from(" ")]);
+            self.push_text(text, ElementType::List)?;
+            self.push_line_break();
         }
+        if item.blocks.is_empty() {
+            return Ok(());
+        }
+
+        let alignment = match self.theme.alignment(&ElementType::List) {
+            Alignment::Left { margin } => Alignment::Left { margin: margin + indentation as u16 },
+            other => other,
+        };
+        let parent_alignment = self.nested_alignment.replace(alignment);
+        for block in item.blocks {
+            self.push_line_break();
+            self.process_element(block)?;
+        }
+        self.push_line_break();
+        self.nested_alignment = parent_alignment;
+        Ok(())
     }
 
-    fn push_list_item(&mut self, item: ListItem) {
-        let padding_length = (item.depth as usize + 1) * 2;
+    // Returns the padding that goes before a list item's marker and the marker itself.
+    fn list_item_marker(&self, item: &ListItem, indentations: &[usize]) -> (String, StyledText) {
+        let style = &self.theme.list;
+        let level = style.level(item.depth).cloned().unwrap_or_default();
+        let padding_length = (item.depth as usize + 1) * style.indentation.unwrap_or(2) as usize;
         let mut prefix: String = " ".repeat(padding_length);
-        match item.item_type {
-            ListItemType::Unordered => {
-                let delimiter = match item.depth {
-                    0 => '•',
-                    1 => '◦',
-                    _ => '▪',
+        let marker = match item.item_type {
+            ListItemType::Unordered => level.bullet.unwrap_or_else(|| {
+                let bullet = match item.depth {
+                    0 => "•",
+                    1 => "◦",
+                    _ => "▪",
                 };
-                prefix.push(delimiter);
-            }
-            ListItemType::OrderedParens(number) => {
-                prefix.push_str(&number.to_string());
-                prefix.push_str(") ");
+                bullet.into()
+            }),
+            ListItemType::OrderedParens(number) => format!("{}) ", level.numbering.format(number)),
+            ListItemType::OrderedPeriod(number) => format!("{}. ", level.numbering.format(number)),
+            // Continuations are aligned with the text of the item they belong to.
+            ListItemType::Continuation => {
+                // There's no indentation stored if the item only contained a nested list, in which
+                // case this is aligned as if it had a bullet.
+                let indentation = indentations
+                    .get(item.depth as usize)
+                    .copied()
+                    .filter(|indentation| *indentation > 0)
+                    .unwrap_or(padding_length + 2);
+                prefix = " ".repeat(indentation - 1);
+                String::new()
             }
-            ListItemType::OrderedPeriod(number) => {
-                prefix.push_str(&number.to_string());
-                prefix.push_str(". ");
+        };
+        let marker = match item.checkbox {
+            Some(checked) => {
+                let style = &self.theme.task_list;
+                let mark = match checked {
+                    true => style.checked.unwrap_or('☑'),
+                    false => style.unchecked.unwrap_or('☐'),
+                };
+                format!("{} {mark}", marker.trim_end())
             }
+            None => marker,
         };
+        (prefix, StyledText::new(marker, TextStyle::default().colors(level.colors)))
+    }
 
-        prefix.push(' ');
-        let mut text = item.contents;
-        text.chunks.insert(0, StyledText::from(prefix));
-        self.push_text(text, ElementType::List);
-        self.push_line_break();
+    fn push_block_quote(&mut self, elements: Vec<MarkdownElement>) -> Result<(), BuildError> {
+        let mut lines = Vec::new();
+        self.collect_quote_lines(elements, 1, &mut lines)?;
+        let generator = BlockQuoteGenerator {
+            lines,
+            prefix: self.theme.block_quote.prefix.clone().unwrap_or_default(),
+            alignment: self.alignment(&ElementType::BlockQuote),
+        };
+        self.slide_operations.push(RenderOperation::SetColors(self.theme.block_quote.colors.clone()));
+        self.slide_operations.push(RenderOperation::RenderDynamic(Rc::new(generator)));
+        self.slide_operations.push(RenderOperation::SetColors(self.theme.default_style.colors.clone()));
+        Ok(())
     }
 
-    fn push_block_quote(&mut self, lines: Vec<String>) {
-        let prefix = self.theme.block_quote.prefix.clone().unwrap_or_default();
-        let block_length = lines.iter().map(|line| line.width() + prefix.width()).max().unwrap_or(0);
+    fn push_alert(
+        &mut self,
+        alert_type: AlertType,
+        title: Option<String>,
+        elements: Vec<MarkdownElement>,
+    ) -> Result<(), BuildError> {
+        let mut lines = Vec::new();
+        self.collect_quote_lines(elements, 0, &mut lines)?;
 
-        self.slide_operations.push(RenderOperation::SetColors(self.theme.block_quote.colors.clone()));
-        for mut line in lines {
-            line.insert_str(0, &prefix);
-
-            let line_length = line.width();
-            self.slide_operations.push(RenderOperation::RenderPreformattedLine(PreformattedLine {
-                text: line,
-                unformatted_length: line_length,
-                block_length,
-                alignment: self.theme.alignment(&ElementType::BlockQuote).clone(),
-            }));
-            self.push_line_break();
-        }
+        let style = &self.theme.alert;
+        let (type_style, default_title, default_icon) = match alert_type {
+            AlertType::Note => (&style.note, "Note", "ℹ"),
+            AlertType::Tip => (&style.tip, "Tip", "☆"),
+            AlertType::Important => (&style.important, "Important", "‼"),
+            AlertType::Warning => (&style.warning, "Warning", "⚠"),
+            AlertType::Caution => (&style.caution, "Caution", "✖"),
+        };
+        let title = title.or_else(|| type_style.title.clone()).unwrap_or_else(|| default_title.into());
+        let icon = type_style.icon.clone().unwrap_or_else(|| default_icon.into());
+        let generator = AlertGenerator {
+            lines,
+            prefix: style.prefix.clone().unwrap_or_default(),
+            title: format!("{icon} {title}"),
+            color: type_style.color,
+            alignment: self.alignment(&ElementType::Alert),
+        };
+        self.slide_operations.push(RenderOperation::SetColors(self.theme.alert.colors.clone()));
+        self.slide_operations.push(RenderOperation::RenderDynamic(Rc::new(generator)));
         self.slide_operations.push(RenderOperation::SetColors(self.theme.default_style.colors.clone()));
+        Ok(())
+    }
+
+    fn collect_quote_lines(
+        &mut self,
+        elements: Vec<MarkdownElement>,
+        depth: usize,
+        lines: &mut Vec<QuoteLine>,
+    ) -> Result<(), BuildError> {
+        let push = |lines: &mut Vec<QuoteLine>, marker, text| lines.push(QuoteLine { depth, marker, text });
+        for (index, element) in elements.into_iter().enumerate() {
+            if index > 0 {
+                push(lines, Vec::new(), Text { chunks: Vec::new() });
+            }
+            match element {
+                MarkdownElement::Paragraph(elements) => {
+                    for element in elements {
+                        if let ParagraphElement::Text(text) = element {
+                            push(lines, Vec::new(), self.style_text(text)?);
+                        }
+                    }
+                }
+                MarkdownElement::Heading { text, .. } | MarkdownElement::SetexHeading { text } => {
+                    let mut text = self.style_text(text)?;
+                    text.apply_style(&TextStyle::default().bold());
+                    push(lines, Vec::new(), text);
+                }
+                MarkdownElement::List(items) => {
+                    let mut indentations = Vec::new();
+                    for item in items {
+                        let (prefix, marker) = self.list_item_marker(&item, &indentations);
+                        let indentation = prefix.width() + marker.text.width() + 1;
+                        indentations.resize(item.depth as usize + 1, 0);
+                        indentations[item.depth as usize] = indentation;
+                        if !item.contents.chunks.is_empty() || item.item_type != ListItemType::Continuation {
+                            let marker = vec![StyledText::from(prefix), marker, StyledText::from(" ")];
+                            push(lines, marker, self.style_text(item.contents)?);
+                        }
+                        for block in item.blocks {
+                            let mut block_lines = Vec::new();
+                            self.collect_quote_lines(vec![block], depth, &mut block_lines)?;
+                            for mut line in block_lines {
+                                line.marker.insert(0, StyledText::from(" ".repeat(indentation)));
+                                lines.push(line);
+                            }
+                        }
+                    }
+                }
+                MarkdownElement::Code(code) => {
+                    for line in code.contents.lines() {
+                        let text = Text::from(StyledText::new(line, TextStyle::default().code()));
+                        push(lines, Vec::new(), self.style_text(text)?);
+                    }
+                }
+                MarkdownElement::BlockQuote(elements) | MarkdownElement::Alert { elements, .. } => {
+                    self.collect_quote_lines(elements, depth + 1, lines)?
+                }
+                // The parser doesn't allow anything else within quotes.
+                _ => (),
+            };
+        }
+        Ok(())
     }
 
-    fn push_text(&mut self, text: Text, element_type: ElementType) {
-        let alignment = self.theme.alignment(&element_type);
-        let mut texts: Vec<WeightedText> = Vec::new();
-        for mut chunk in text.chunks {
+    fn push_text(&mut self, text: Text, element_type: ElementType) -> Result<(), BuildError> {
+        let alignment = self.alignment(&element_type);
+        let texts: Vec<WeightedText> = self.style_text(text)?.chunks.into_iter().map(WeightedText::from).collect();
+        if !texts.is_empty() {
+            self.slide_operations.push(RenderOperation::RenderTextLine { line: WeightedLine::from(texts), alignment });
+        }
+        Ok(())
+    }
+
+    // Resolves everything in the given text that depends on the theme or on the rest of the
+    // presentation.
+    fn style_text(&mut self, text: Text) -> Result<Text, BuildError> {
+        let mut chunks = text.chunks;
+        self.resolve_footnotes(&mut chunks);
+        if self.theme.link.mode == LinkMode::LabelAndUrl {
+            chunks = Self::expand_links(chunks);
+        }
+        for chunk in &mut chunks {
+            // Code is displayed as is so it can contain anything that looks like a variable.
+            if !chunk.style.is_code() {
+                chunk.text = self.expand_variables(&chunk.text);
+            }
+            if let Some(class) = &chunk.class {
+                let colors =
+                    self.theme.palette.classes.get(class).ok_or_else(|| BuildError::UndefinedClass(class.clone()))?;
+                // Colors set explicitly via a `style` attribute take precedence over the class'.
+                chunk.style.colors.foreground = chunk.style.colors.foreground.or(colors.foreground);
+                chunk.style.colors.background = chunk.style.colors.background.or(colors.background);
+            }
             if chunk.style.is_code() {
                 chunk.style.colors = self.theme.inline_code.colors.clone();
             }
-            texts.push(chunk.into());
         }
-        if !texts.is_empty() {
-            self.slide_operations.push(RenderOperation::RenderTextLine {
-                line: WeightedLine::from(texts),
-                alignment: alignment.clone(),
-            });
+        Ok(Text { chunks })
+    }
+
+    // Replaces every `{{name}}` with the value of the variable with that name. Anything that doesn't
+    // reference a variable is left as is and `{{{{` can be used to display a literal `{{`.
+    fn expand_variables(&self, text: &str) -> String {
+        let mut output = String::new();
+        let mut remaining = text;
+        while let Some(start) = remaining.find("{{") {
+            output.push_str(&remaining[..start]);
+            remaining = &remaining[start + 2..];
+            if let Some(rest) = remaining.strip_prefix("{{") {
+                output.push_str("{{");
+                remaining = rest;
+                continue;
+            }
+            let variable =
+                remaining.find("}}").and_then(|end| Some((self.variables.get(remaining[..end].trim())?, end)));
+            match variable {
+                Some((value, end)) => {
+                    output.push_str(value);
+                    remaining = &remaining[end + 2..];
+                }
+                None => output.push_str("{{"),
+            };
+        }
+        output.push_str(remaining);
+        output
+    }
+
+    // Turns every link into its label followed by the URL it points to.
+    fn expand_links(chunks: Vec<StyledText>) -> Vec<StyledText> {
+        let mut output = Vec::new();
+        let mut label = String::new();
+        let mut chunks = chunks.into_iter().peekable();
+        while let Some(mut chunk) = chunks.next() {
+            let Some(url) = chunk.style.url.take() else {
+                output.push(chunk);
+                continue;
+            };
+            label.push_str(&chunk.text);
+            output.push(chunk);
+
+            // A link can span multiple chunks if parts of its label are styled differently.
+            let link_continues = chunks.peek().is_some_and(|next| next.style.url.as_ref() == Some(&url));
+            if !link_continues && mem::take(&mut label) != url {
+                output.push(StyledText::from(format!(" ({url})")));
+            }
+        }
+        output
+    }
+
+    // Turns every footnote reference into the marker for that footnote.
+    fn resolve_footnotes(&mut self, chunks: &mut [StyledText]) {
+        for chunk in chunks {
+            let Some(name) = chunk.footnote.take() else {
+                continue;
+            };
+            let Some(footnote) = self.footnotes.get(&name) else {
+                continue;
+            };
+            chunk.text = footnote.marker();
+            if !self.slide_footnotes.contains(&name) {
+                self.slide_footnotes.push(name);
+            }
+        }
+    }
+
+    fn push_footnotes(&mut self) -> Result<(), BuildError> {
+        let names = mem::take(&mut self.slide_footnotes);
+        let mut footnotes: Vec<_> = names.iter().filter_map(|name| self.footnotes.get(name)).cloned().collect();
+        if footnotes.is_empty() {
+            return Ok(());
         }
+        footnotes.sort_by_key(|footnote| footnote.number);
+        self.slide_operations.push(RenderOperation::JumpToBottom);
+        for footnote in footnotes {
+            let mut text = Text::from(format!("{} ", footnote.marker()));
+            text.chunks.extend(footnote.text.chunks);
+            self.push_text(text, ElementType::Paragraph)?;
+            self.push_line_break();
+        }
+        Ok(())
     }
 
     fn push_line_break(&mut self) {
         self.slide_operations.push(RenderOperation::RenderLineBreak);
     }
 
-    fn push_code(&mut self, code: Code) {
-        let Code { contents, language } = code;
+    fn push_code(&mut self, code: Code) -> Result<(), BuildError> {
+        if code.attributes.execute && !CodeExecuter::is_execution_supported(&code.language) {
+            return Err(BuildError::UnsupportedExecution(code.language));
+        }
+        let executable = code.attributes.execute.then(|| code.clone());
+        let Code { contents, language, attributes, .. } = code;
+        let line_count = contents.lines().count();
         let mut code = String::new();
         let horizontal_padding = self.theme.code.padding.horizontal.unwrap_or(0);
         let vertical_padding = self.theme.code.padding.vertical.unwrap_or(0);
@@ -379,88 +746,144 @@ impl<'a> PresentationBuilder<'a> {
                 code.push('\n');
             }
         }
-        let block_length = code.lines().map(|line| line.width()).max().unwrap_or(0) + horizontal_padding as usize;
-        for code_line in self.highlighter.highlight(&code, &language) {
-            let CodeLine { formatted, original } = code_line;
-            let trimmed = formatted.trim_end();
-            let original_length = original.width() - (formatted.width() - trimmed.width());
-            self.slide_operations.push(RenderOperation::RenderPreformattedLine(PreformattedLine {
-                text: trimmed.into(),
-                unformatted_length: original_length,
-                block_length,
-                alignment: self.theme.alignment(&ElementType::Code).clone(),
-            }));
-            self.push_line_break();
+        let line_numbers_style = &self.theme.code.line_numbers;
+        let gutter_width =
+            if attributes.line_numbers || line_numbers_style.enabled { line_count.to_string().len() + 1 } else { 0 };
+        let gutter_style = TextStyle::default().colors(line_numbers_style.colors.clone());
+        let block_length =
+            code.lines().map(|line| line.width()).max().unwrap_or(0) + horizontal_padding as usize + gutter_width;
+        let alignment = self.alignment(&ElementType::Code);
+        let lines = self.highlighter.highlight(&code, &language);
+        let run_code = executable.map(|code| {
+            let default_colors = self.theme.default_style.colors.clone();
+            let output_colors = &self.theme.execution_output.colors;
+            let block_colors = Colors {
+                foreground: output_colors.foreground.or(default_colors.foreground),
+                background: output_colors.background.or(default_colors.background),
+            };
+            Rc::new(RunCodeOperation::new(code, default_colors, block_colors, block_length, alignment.clone()))
+        });
+
+        let mut groups = attributes.highlight_groups;
+        if groups.is_empty() {
+            groups.push(HighlightGroup::new(vec![Highlight::All]));
+        }
+        let start = self.slide_operations.len();
+        for (index, group) in groups.iter().enumerate() {
+            // Every highlight group after the first one is shown after a pause.
+            if index > 0 {
+                self.push_pause()?;
+                self.slide_operations.truncate(start);
+            }
+            for (line_index, code_line) in lines.iter().enumerate() {
+                // The vertical padding is not part of the code so it doesn't count towards line numbers.
+                let line_number = line_index + 1 - usize::from(vertical_padding > 0);
+                let highlighted = u16::try_from(line_number).map(|number| group.contains(number)).unwrap_or(false);
+                let CodeLine { formatted, dimmed, .. } = code_line;
+                let formatted = if highlighted || line_number == 0 { formatted } else { dimmed };
+                let mut text = Vec::new();
+                if gutter_width > 0 {
+                    let number =
+                        if (1..=line_count).contains(&line_number) { line_number.to_string() } else { String::new() };
+                    let gutter = format!("{number:>0$} ", gutter_width - 1);
+                    text.push(StyledText::new(gutter, gutter_style.clone()));
+                }
+                text.extend(Self::trim_code_line(formatted));
+                let unformatted_length = text.iter().map(|chunk| chunk.text.width()).sum();
+                self.slide_operations.push(RenderOperation::RenderPreformattedLine(PreformattedLine {
+                    text,
+                    unformatted_length,
+                    block_length,
+                    alignment: alignment.clone(),
+                }));
+                self.push_line_break();
+            }
+            if let Some(operation) = &run_code {
+                self.slide_operations.push(RenderOperation::RenderOnDemand(operation.clone()));
+            }
+        }
+        // Pausing makes us skip the line break after this element, which we don't want here.
+        self.ignore_element_line_break = false;
+        Ok(())
+    }
+
+    // Trims the whitespace at the end of a line of code.
+    //
+    // Pieces of text that end up empty are kept around as their background color is the one used to
+    // pad the line.
+    fn trim_code_line(line: &[StyledText]) -> Vec<StyledText> {
+        let mut line = line.to_vec();
+        for chunk in line.iter_mut().rev() {
+            chunk.text.truncate(chunk.text.trim_end().len());
+            if !chunk.text.is_empty() {
+                break;
+            }
         }
+        line
     }
 
-    fn terminate_slide(&mut self) {
-        self.push_footer();
+    fn terminate_slide(&mut self) -> Result<(), BuildError> {
+        // Footnotes and the footer use the entire slide so get out of any layout first.
+        if !matches!(self.layout, LayoutState::Default) {
+            self.layout = LayoutState::Default;
+            self.slide_operations.push(RenderOperation::ExitLayout);
+        }
+        self.push_footnotes()?;
+        self.push_footer()?;
 
         let elements = mem::take(&mut self.slide_operations);
-        self.slides.push(Slide { render_operations: elements });
+        let notes = mem::take(&mut self.slide_notes);
+        let after_pause = mem::take(&mut self.slide_after_pause);
+        self.slides.push(Slide { render_operations: elements, notes, after_pause });
         self.push_slide_prelude();
         self.ignore_element_line_break = true;
+        Ok(())
     }
 
-    fn push_footer(&mut self) {
-        let generator = FooterGenerator {
-            style: self.theme.footer.clone(),
-            current_slide: self.slides.len(),
-            context: self.footer_context.clone(),
-        };
-        self.slide_operations.push(RenderOperation::RenderDynamic(Rc::new(generator)));
-    }
-
-    fn push_table(&mut self, table: Table) {
-        let widths: Vec<_> = (0..table.columns())
-            .map(|column| table.iter_column(column).map(|text| text.width()).max().unwrap_or(0))
-            .collect();
-        let flattened_header = Self::prepare_table_row(table.header, &widths);
-        self.push_text(flattened_header, ElementType::Table);
-        self.push_line_break();
-
-        let mut separator = Text { chunks: Vec::new() };
-        for (index, width) in widths.iter().enumerate() {
-            let mut contents = String::new();
-            let mut margin = 1;
-            if index > 0 {
-                contents.push('┼');
-                // Append an extra dash to have 1 column margin on both sides
-                if index < widths.len() - 1 {
-                    margin += 1;
-                }
+    fn push_footer(&mut self) -> Result<(), BuildError> {
+        let mut style = self.theme.footer.clone();
+        if let FooterStyle::Template { left, right, .. } = &mut style {
+            for template in [left, right].into_iter().flatten() {
+                *template = self.expand_variables(template);
             }
-            contents.extend(iter::repeat("─").take(*width + margin));
-            separator.chunks.push(StyledText::from(contents));
         }
+        let generator =
+            FooterGenerator { style, current_slide: self.slides.len(), context: self.footer_context.clone() };
+        self.slide_operations.push(RenderOperation::RenderDynamic(Rc::new(generator)));
+        Ok(())
+    }
 
-        self.push_text(separator, ElementType::Table);
-        self.push_line_break();
+    fn push_table(&mut self, table: Table) -> Result<(), BuildError> {
+        // How the table is laid out depends on how much room there is for it, so it can only be
+        // done while rendering.
+        let Table { header, rows, alignments } = table;
+        let header = self.style_table_row(header)?;
+        let rows = rows.into_iter().map(|row| self.style_table_row(row)).collect::<Result<_, _>>()?;
+        let generator = TableGenerator {
+            table: Table { header, rows, alignments },
+            alignment: self.alignment(&ElementType::Table),
+            style: self.theme.table.clone(),
+        };
+        self.slide_operations.push(RenderOperation::RenderDynamic(Rc::new(generator)));
+        Ok(())
+    }
 
-        for row in table.rows {
-            let flattened_row = Self::prepare_table_row(row, &widths);
-            self.push_text(flattened_row, ElementType::Table);
-            self.push_line_break();
-        }
+    fn style_table_row(&mut self, row: TableRow) -> Result<TableRow, BuildError> {
+        let cells = row.0.into_iter().map(|cell| self.style_text(cell)).collect::<Result<_, _>>()?;
+        Ok(TableRow(cells))
     }
+}
 
-    fn prepare_table_row(row: TableRow, widths: &[usize]) -> Text {
-        let mut flattened_row = Text { chunks: Vec::new() };
-        for (column, text) in row.0.into_iter().enumerate() {
-            if column > 0 {
-                flattened_row.chunks.push(StyledText::from(" │ "));
-            }
-            let text_length = text.width();
-            flattened_row.chunks.extend(text.chunks.into_iter());
+#[derive(Clone, Debug)]
+struct Footnote {
+    number: usize,
+    text: Text,
+}
 
-            let cell_width = widths[column];
-            if text_length < cell_width {
-                let padding = " ".repeat(cell_width - text_length);
-                flattened_row.chunks.push(StyledText::from(padding));
-            }
-        }
-        flattened_row
+impl Footnote {
+    fn marker(&self) -> String {
+        const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+        self.number.to_string().chars().filter_map(|c| c.to_digit(10)).map(|digit| DIGITS[digit as usize]).collect()
     }
 }
 
@@ -468,6 +891,20 @@ impl<'a> PresentationBuilder<'a> {
 struct FooterContext {
     total_slides: usize,
     author: String,
+    duration: Option<Duration>,
+    clock: PresentationClock,
+}
+
+impl FooterContext {
+    // The presentation is behind schedule if the time allotted to the current slide, assuming
+    // every slide takes the same amount of time, has already passed.
+    fn is_behind_schedule(&self, current_slide: usize, elapsed: Duration) -> bool {
+        let Some(duration) = self.duration else {
+            return false;
+        };
+        let expected = duration.mul_f64((current_slide + 1) as f64 / self.total_slides.max(1) as f64);
+        elapsed > expected
+    }
 }
 
 #[derive(Debug)]
@@ -478,11 +915,27 @@ struct FooterGenerator {
 }
 
 impl FooterGenerator {
-    fn render_template(template: &str, current_slide: &str, context: &FooterContext, colors: Colors) -> WeightedText {
-        let contents = template
-            .replace("{current_slide}", current_slide)
+    const TIME_PLACEHOLDERS: &'static [&'static str] = &["{elapsed}", "{clock}", "{remaining}"];
+
+    fn render_template(
+        &self,
+        template: &str,
+        context: &FooterContext,
+        elapsed: Duration,
+        colors: Colors,
+    ) -> WeightedText {
+        // Only whole seconds are displayed so use those to keep both values in sync.
+        let elapsed = Duration::from_secs(elapsed.as_secs());
+        let remaining = context.duration.map(|duration| format_duration(duration.saturating_sub(elapsed)));
+        let mut contents = template
+            .replace("{current_slide}", &(self.current_slide + 1).to_string())
             .replace("{total_slides}", &context.total_slides.to_string())
-            .replace("{author}", &context.author);
+            .replace("{author}", &context.author)
+            .replace("{elapsed}", &format_duration(elapsed))
+            .replace("{remaining}", &remaining.unwrap_or_default());
+        if contents.contains("{clock}") {
+            contents = contents.replace("{clock}", &Local::now().format("%H:%M").to_string());
+        }
         WeightedText::from(StyledText::new(contents, TextStyle::default().colors(colors)))
     }
 }
@@ -491,14 +944,19 @@ impl AsRenderOperations for FooterGenerator {
     fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
         let context = self.context.borrow();
         match &self.style {
-            FooterStyle::Template { left, right, colors } => {
-                let current_slide = (self.current_slide + 1).to_string();
+            FooterStyle::Template { left, right, colors, warning_colors } => {
+                let elapsed = context.clock.elapsed();
+                let colors = match warning_colors {
+                    _ if !context.is_behind_schedule(self.current_slide, elapsed) => colors.clone(),
+                    Some(warning_colors) => warning_colors.clone(),
+                    None => Colors { foreground: Some(Color::Red), background: colors.background },
+                };
                 let mut operations = Vec::new();
                 if let Some(left) = left {
                     operations.extend([
                         RenderOperation::JumpToWindowBottom,
                         RenderOperation::RenderTextLine {
-                            line: vec![Self::render_template(left, &current_slide, &context, colors.clone())].into(),
+                            line: vec![self.render_template(left, &context, elapsed, colors.clone())].into(),
                             alignment: Alignment::Left { margin: 1 },
                         },
                     ]);
@@ -507,7 +965,7 @@ impl AsRenderOperations for FooterGenerator {
                     operations.extend([
                         RenderOperation::JumpToWindowBottom,
                         RenderOperation::RenderTextLine {
-                            line: vec![Self::render_template(right, &current_slide, &context, colors.clone())].into(),
+                            line: vec![self.render_template(right, &context, elapsed, colors.clone())].into(),
                             alignment: Alignment::Right { margin: 1 },
                         },
                     ]);
@@ -529,85 +987,654 @@ impl AsRenderOperations for FooterGenerator {
             FooterStyle::Empty => vec![],
         }
     }
-}
-
-/// An error when building a presentation.
-#[derive(thiserror::Error, Debug)]
-pub enum BuildError {
-    #[error("loading image: {0}")]
-    LoadImage(#[from] LoadImageError),
 
-    #[error("invalid presentation metadata: {0}")]
-    InvalidMetadata(String),
-
-    #[error("invalid theme: {0}")]
-    InvalidTheme(#[from] LoadThemeError),
+    fn is_time_dependent(&self) -> bool {
+        match &self.style {
+            // The colors change when falling behind schedule so any template is affected by the duration.
+            FooterStyle::Template { left, right, .. } => {
+                let uses_time = |template: &Option<String>| {
+                    template.as_ref().is_some_and(|template| {
+                        Self::TIME_PLACEHOLDERS.iter().any(|placeholder| template.contains(placeholder))
+                    })
+                };
+                self.context.borrow().duration.is_some() || uses_time(left) || uses_time(right)
+            }
+            FooterStyle::ProgressBar { .. } | FooterStyle::Empty => false,
+        }
+    }
+}
 
-    #[error("invalid code highlighter theme")]
-    InvalidCodeTheme,
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    format!("{:02}:{:02}:{:02}", seconds / 3600, (seconds / 60) % 60, seconds % 60)
 }
 
-enum Comment {
-    Pause,
-    EndSlide,
+// Parses durations like `45s`, `20m` or `1h30m`.
+fn parse_duration(input: &str) -> Option<Duration> {
+    let mut seconds = 0;
+    let mut number = String::new();
+    for c in input.trim().chars() {
+        let unit = match c {
+            '0'..='9' => {
+                number.push(c);
+                continue;
+            }
+            'h' => 3600,
+            'm' => 60,
+            's' => 1,
+            _ => return None,
+        };
+        seconds = number.parse::<u64>().ok()?.checked_mul(unit)?.checked_add(seconds)?;
+        number.clear();
+    }
+    (number.is_empty() && seconds > 0).then(|| Duration::from_secs(seconds))
 }
 
-impl FromStr for Comment {
-    type Err = ();
+#[derive(Debug)]
+struct TableGenerator {
+    table: Table,
+    alignment: Alignment,
+    style: TableStyle,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "pause" => Ok(Self::Pause),
-            "end_slide" => Ok(Self::EndSlide),
-            _ => Err(()),
+impl TableGenerator {
+    // Shrinks the widest columns until they all fit in the given width.
+    fn fit_widths(widths: &[usize], available: usize) -> Vec<usize> {
+        if widths.iter().sum::<usize>() <= available {
+            return widths.to_vec();
+        }
+        // Columns narrower than an even share of the remaining width are left untouched, and the
+        // rest split whatever's left between them.
+        let mut fitted = widths.to_vec();
+        let mut remaining = available;
+        let mut pending: Vec<usize> = (0..widths.len()).collect();
+        loop {
+            let share = remaining / pending.len();
+            let (narrow, wide): (Vec<usize>, Vec<usize>) = pending.iter().partition(|column| widths[**column] <= share);
+            if narrow.is_empty() {
+                for (index, column) in wide.iter().enumerate() {
+                    let extra = usize::from(index < remaining % wide.len());
+                    fitted[*column] = (share + extra).max(1);
+                }
+                return fitted;
+            }
+            remaining -= narrow.iter().map(|column| widths[*column]).sum::<usize>();
+            pending = wide;
+            if pending.is_empty() {
+                return fitted;
+            }
         }
     }
-}
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use crate::{markdown::elements::ProgrammingLanguage, presentation::PreformattedLine};
+    fn render_row(&self, row: &TableRow, widths: &[usize], colors: &Colors) -> Vec<WeightedLine> {
+        let cells: Vec<_> = row.0.iter().zip(widths).map(|(cell, width)| wrap_text(cell, *width)).collect();
+        let height = cells.iter().map(Vec::len).max().unwrap_or(0).max(1);
+        let padding = |length: usize| StyledText::new(" ".repeat(length), TextStyle::default().colors(colors.clone()));
+        let separator = match self.style.borders {
+            TableBorders::None => "  ",
+            TableBorders::Inner | TableBorders::Full => " │ ",
+        };
 
-    fn build_presentation(elements: Vec<MarkdownElement>) -> Presentation {
-        let highlighter = CodeHighlighter::new("base16-ocean.dark").unwrap();
-        let theme = PresentationTheme::default();
-        let mut resources = Resources::new("/tmp");
-        let builder = PresentationBuilder::new(highlighter, &theme, &mut resources);
-        builder.build(elements).expect("build failed")
+        let mut lines = Vec::new();
+        for line_index in 0..height {
+            let mut line = Vec::new();
+            if self.style.borders == TableBorders::Full {
+                line.push(StyledText::new("│ ", TextStyle::default().colors(colors.clone())));
+            }
+            for (column, cell) in cells.iter().enumerate() {
+                if column > 0 {
+                    line.push(StyledText::new(separator, TextStyle::default().colors(colors.clone())));
+                }
+                let mut chunks = cell.get(line_index).cloned().unwrap_or_default();
+                for chunk in &mut chunks {
+                    chunk.style.colors.foreground = chunk.style.colors.foreground.or(colors.foreground);
+                    chunk.style.colors.background = chunk.style.colors.background.or(colors.background);
+                }
+                let width: usize = chunks.iter().map(|chunk| chunk.text.width()).sum();
+                let missing = widths[column].saturating_sub(width);
+                let alignment = self.table.alignments.get(column).cloned().unwrap_or_default();
+                let (before, after) = match alignment {
+                    ColumnAlignment::Left => (0, missing),
+                    ColumnAlignment::Center => (missing / 2, missing - missing / 2),
+                    ColumnAlignment::Right => (missing, 0),
+                };
+                if before > 0 {
+                    line.push(padding(before));
+                }
+                line.extend(chunks);
+                if after > 0 {
+                    line.push(padding(after));
+                }
+            }
+            if self.style.borders == TableBorders::Full {
+                line.push(StyledText::new(" │", TextStyle::default().colors(colors.clone())));
+            }
+            lines.push(WeightedLine::from(line.into_iter().map(WeightedText::from).collect::<Vec<_>>()));
+        }
+        lines
     }
 
-    fn is_visible(operation: &RenderOperation) -> bool {
-        use RenderOperation::*;
-        match operation {
-            ClearScreen | SetColors(_) | JumpToVerticalCenter | JumpToSlideBottom | JumpToWindowBottom => false,
-            _ => true,
+    fn render_border(&self, widths: &[usize], left: &str, junction: &str, right: &str) -> WeightedLine {
+        let full = self.style.borders == TableBorders::Full;
+        let mut border = String::from(left);
+        for (column, width) in widths.iter().enumerate() {
+            if column > 0 {
+                border.push_str(junction);
+            }
+            // Every column is surrounded by a space on each side, except on the table's edges
+            // unless they have a border.
+            let margins = usize::from(column > 0 || full) + usize::from(column < widths.len() - 1 || full);
+            border.push_str(&"─".repeat(width + margins));
         }
+        border.push_str(right);
+        WeightedLine::from(border)
     }
+}
 
-    fn extract_text_lines(operations: &[RenderOperation]) -> Vec<String> {
-        let mut output = Vec::new();
-        for operation in operations {
-            match operation {
-                RenderOperation::RenderTextLine { line, .. } => {
-                    let texts: Vec<_> = line.iter_texts().map(|text| text.text.text.clone()).collect();
-                    output.push(texts.join(""));
-                }
-                _ => (),
+impl AsRenderOperations for TableGenerator {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let columns = self.table.columns();
+        if columns == 0 {
+            return Vec::new();
+        }
+        let decorations = match self.style.borders {
+            TableBorders::None => 2 * (columns - 1),
+            TableBorders::Inner => 3 * (columns - 1),
+            TableBorders::Full => 3 * (columns - 1) + 4,
+        };
+        let available = Layout::new(&self.alignment).compute(dimensions, u16::MAX).max_line_length as usize;
+        let widths: Vec<_> = (0..columns)
+            .map(|column| self.table.iter_column(column).map(|text| text.width()).max().unwrap_or(0))
+            .collect();
+        let widths = Self::fit_widths(&widths, available.saturating_sub(decorations));
+
+        let full = self.style.borders == TableBorders::Full;
+        let mut lines = Vec::new();
+        if full {
+            lines.push(self.render_border(&widths, "┌", "┬", "┐"));
+        }
+        lines.extend(self.render_row(&self.table.header, &widths, &self.style.header_colors));
+        match self.style.borders {
+            TableBorders::None => (),
+            TableBorders::Inner => lines.push(self.render_border(&widths, "", "┼", "")),
+            TableBorders::Full => lines.push(self.render_border(&widths, "├", "┼", "┤")),
+        };
+        for (index, row) in self.table.rows.iter().enumerate() {
+            let colors = match &self.style.alternate_row_colors {
+                Some(colors) if index % 2 == 1 => colors,
+                _ => &self.style.row_colors,
             };
+            lines.extend(self.render_row(row, &widths, colors));
         }
-        output
+        if full {
+            lines.push(self.render_border(&widths, "└", "┴", "┘"));
+        }
+
+        let mut operations = Vec::new();
+        for line in lines {
+            operations.push(RenderOperation::RenderTextLine { line, alignment: self.alignment.clone() });
+            operations.push(RenderOperation::RenderLineBreak);
+        }
+        operations
     }
+}
 
-    #[test]
-    fn prelude_appears_once() {
-        let elements = vec![
-            MarkdownElement::FrontMatter("author: bob".to_string()),
-            MarkdownElement::Heading { text: Text::from("hello"), level: 1 },
-            MarkdownElement::Comment("end_slide".to_string()),
-            MarkdownElement::Heading { text: Text::from("bye"), level: 1 },
-        ];
-        let presentation = build_presentation(elements);
+/// A line within a quote.
+#[derive(Debug)]
+struct QuoteLine {
+    depth: usize,
+    marker: Vec<StyledText>,
+    text: Text,
+}
+
+#[derive(Debug)]
+struct BlockQuoteGenerator {
+    lines: Vec<QuoteLine>,
+    prefix: String,
+    alignment: Alignment,
+}
+
+impl AsRenderOperations for BlockQuoteGenerator {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let available = Layout::new(&self.alignment).compute(dimensions, u16::MAX).max_line_length as usize;
+        let lines = wrap_quote_lines(&self.lines, &self.prefix, available);
+
+        // Pad every line so the quote's background is a rectangle.
+        let block_length = lines.iter().map(|line| line_width(line)).max().unwrap_or(0);
+        let block_length = Layout::new(&self.alignment).compute(dimensions, block_length as u16).max_line_length;
+        let mut operations = Vec::new();
+        for mut line in lines {
+            let missing = (block_length as usize).saturating_sub(line_width(&line));
+            line.push(StyledText::from(" ".repeat(missing)));
+            let line = WeightedLine::from(line.into_iter().map(WeightedText::from).collect::<Vec<_>>());
+            operations.push(RenderOperation::RenderTextLine { line, alignment: self.alignment.clone() });
+            operations.push(RenderOperation::RenderLineBreak);
+        }
+        operations
+    }
+}
+
+#[derive(Debug)]
+struct AlertGenerator {
+    lines: Vec<QuoteLine>,
+    prefix: String,
+    title: String,
+    color: Option<Color>,
+    alignment: Alignment,
+}
+
+impl AsRenderOperations for AlertGenerator {
+    fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let available = Layout::new(&self.alignment).compute(dimensions, u16::MAX).max_line_length as usize;
+        // The contents are surrounded by the border and a space on each side.
+        let lines = wrap_quote_lines(&self.lines, &self.prefix, available.saturating_sub(4));
+        let content_width = lines.iter().map(|line| line_width(line)).max().unwrap_or(0);
+        let box_width = (content_width + 4).max(self.title.width() + 6);
+        let box_width = Layout::new(&self.alignment).compute(dimensions, box_width as u16).max_line_length as usize;
+
+        let border_style = TextStyle::default().colors(Colors { foreground: self.color, background: None });
+        let border = |text: String| StyledText::new(text, border_style.clone());
+        let title_dashes = box_width.saturating_sub(self.title.width() + 5);
+        let mut output = vec![vec![
+            border("┌─ ".into()),
+            StyledText::new(self.title.clone(), border_style.clone().bold()),
+            border(format!(" {}┐", "─".repeat(title_dashes))),
+        ]];
+        for mut line in lines {
+            let missing = box_width.saturating_sub(line_width(&line) + 4);
+            line.insert(0, border("│ ".into()));
+            line.push(StyledText::from(" ".repeat(missing)));
+            line.push(border(" │".into()));
+            output.push(line);
+        }
+        output.push(vec![border(format!("└{}┘", "─".repeat(box_width.saturating_sub(2))))]);
+
+        let mut operations = Vec::new();
+        for line in output {
+            let line = WeightedLine::from(line.into_iter().map(WeightedText::from).collect::<Vec<_>>());
+            operations.push(RenderOperation::RenderTextLine { line, alignment: self.alignment.clone() });
+            operations.push(RenderOperation::RenderLineBreak);
+        }
+        operations
+    }
+}
+
+// Wraps the lines in a quote so they fit in the given width, adding the prefix once per nesting
+// level in every line.
+fn wrap_quote_lines(lines: &[QuoteLine], prefix: &str, available: usize) -> Vec<Vec<StyledText>> {
+    let mut output = Vec::new();
+    for line in lines {
+        let prefix = prefix.repeat(line.depth);
+        let marker_width = line_width(&line.marker);
+        let width = available.saturating_sub(prefix.width() + marker_width).max(1);
+        let mut wrapped = wrap_text(&line.text, width);
+        if wrapped.is_empty() {
+            wrapped.push(Vec::new());
+        }
+        // The prefix goes in every line but the marker only goes in the first one.
+        for (index, chunks) in wrapped.into_iter().enumerate() {
+            let mut wrapped_line = vec![StyledText::from(prefix.clone())];
+            match index {
+                0 => wrapped_line.extend(line.marker.iter().cloned()),
+                _ => wrapped_line.push(StyledText::from(" ".repeat(marker_width))),
+            };
+            wrapped_line.extend(chunks);
+            output.push(wrapped_line);
+        }
+    }
+    output
+}
+
+fn line_width(line: &[StyledText]) -> usize {
+    line.iter().map(|chunk| chunk.text.width()).sum()
+}
+
+// Wraps a piece of text so none of its lines are longer than the given width.
+fn wrap_text(text: &Text, width: usize) -> Vec<Vec<StyledText>> {
+    let texts: Vec<_> = text.chunks.iter().cloned().map(WeightedText::from).collect();
+    WeightedLine::from(texts)
+        .split(width)
+        .map(|line| {
+            line.into_iter()
+                .map(|chunk| {
+                    let (text, style) = chunk.into_parts();
+                    StyledText::new(text, style)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+/// An error when building a presentation.
+#[derive(thiserror::Error, Debug)]
+pub enum BuildError {
+    #[error("loading image: {0}")]
+    LoadImage(#[from] LoadImageError),
+
+    #[error("invalid presentation metadata: {0}")]
+    InvalidMetadata(String),
+
+    #[error("invalid theme: {0}")]
+    InvalidTheme(#[from] LoadThemeError),
+
+    #[error("invalid code highlighter theme")]
+    InvalidCodeTheme,
+
+    #[error("code execution is not supported for {0:?}")]
+    UnsupportedExecution(ProgrammingLanguage),
+
+    #[error("invalid arguments for '{0}' command")]
+    InvalidCommentArguments(&'static str),
+
+    #[error("invalid layout: {0}")]
+    InvalidLayout(&'static str),
+
+    #[error("class '{0}' is not defined in the theme's palette")]
+    UndefinedClass(String),
+}
+
+#[derive(Clone, Debug, Default)]
+enum LayoutState {
+    #[default]
+    Default,
+    InLayout {
+        columns_count: usize,
+    },
+    InColumn {
+        columns_count: usize,
+    },
+}
+
+#[derive(Debug)]
+enum RunCodeState {
+    NotStarted,
+    Running(ExecutionHandle),
+    Failed(String),
+}
+
+/// Runs a piece of code when requested and renders its output in a bordered block.
+#[derive(Debug)]
+struct RunCodeOperation {
+    code: Code,
+    default_colors: Colors,
+    block_colors: Colors,
+    block_length: usize,
+    alignment: Alignment,
+    state: RefCell<RunCodeState>,
+}
+
+impl RunCodeOperation {
+    const MINIMUM_BLOCK_LENGTH: usize = 30;
+
+    fn new(
+        code: Code,
+        default_colors: Colors,
+        block_colors: Colors,
+        block_length: usize,
+        alignment: Alignment,
+    ) -> Self {
+        Self {
+            code,
+            default_colors,
+            block_colors,
+            block_length: block_length.max(Self::MINIMUM_BLOCK_LENGTH),
+            alignment,
+            state: RefCell::new(RunCodeState::NotStarted),
+        }
+    }
+
+    fn render_line(&self, line: String, operations: &mut Vec<RenderOperation>) {
+        operations.push(RenderOperation::RenderPreformattedLine(PreformattedLine {
+            unformatted_length: line.width(),
+            text: vec![StyledText::from(line)],
+            block_length: self.block_length,
+            alignment: self.alignment.clone(),
+        }));
+        operations.push(RenderOperation::RenderLineBreak);
+    }
+
+    fn render_border(&self, left: char, label: &str, right: char) -> String {
+        let label = format!("─ {label} ");
+        let fill = self.block_length.saturating_sub(label.width() + 2);
+        format!("{left}{label}{}{right}", "─".repeat(fill))
+    }
+
+    fn render_output_line(&self, line: &str) -> String {
+        let max_width = self.block_length.saturating_sub(4);
+        let mut contents = String::new();
+        let mut width = 0;
+        for c in sanitize_output(line).chars() {
+            let char_width = c.width().unwrap_or(0);
+            if width + char_width > max_width {
+                break;
+            }
+            contents.push(c);
+            width += char_width;
+        }
+        format!("│ {contents}{} │", " ".repeat(max_width - width))
+    }
+}
+
+impl AsRenderOperations for RunCodeOperation {
+    fn as_render_operations(&self, _dimensions: &WindowSize) -> Vec<RenderOperation> {
+        let (output, status) = match &*self.state.borrow() {
+            RunCodeState::NotStarted => return Vec::new(),
+            RunCodeState::Running(handle) => {
+                let ExecutionState { output, status } = handle.state();
+                (output, status)
+            }
+            RunCodeState::Failed(error) => (vec![error.clone()], ProcessStatus::Failure(None)),
+        };
+        let status = match status {
+            ProcessStatus::Running => "running".to_string(),
+            ProcessStatus::Success => "finished".to_string(),
+            ProcessStatus::Failure(Some(code)) => format!("failed with exit code {code}"),
+            ProcessStatus::Failure(None) => "failed".to_string(),
+        };
+        let mut operations =
+            vec![RenderOperation::RenderLineBreak, RenderOperation::SetColors(self.block_colors.clone())];
+        self.render_line(self.render_border('╭', "output", '╮'), &mut operations);
+        for line in &output {
+            self.render_line(self.render_output_line(line), &mut operations);
+        }
+        self.render_line(self.render_border('╰', &status, '╯'), &mut operations);
+        operations.push(RenderOperation::SetColors(self.default_colors.clone()));
+        operations
+    }
+}
+
+impl RenderOnDemand for RunCodeOperation {
+    fn start_render(&self) -> bool {
+        let mut state = self.state.borrow_mut();
+        if !matches!(*state, RunCodeState::NotStarted) {
+            return false;
+        }
+        *state = match CodeExecuter::execute(&self.code) {
+            Ok(handle) => RunCodeState::Running(handle),
+            Err(e) => RunCodeState::Failed(e.to_string()),
+        };
+        true
+    }
+
+    fn poll_state(&self) -> RenderOnDemandState {
+        match &*self.state.borrow() {
+            RunCodeState::NotStarted => RenderOnDemandState::NotStarted,
+            RunCodeState::Running(handle) if handle.state().status == ProcessStatus::Running => {
+                RenderOnDemandState::Rendering
+            }
+            RunCodeState::Running(_) | RunCodeState::Failed(_) => RenderOnDemandState::Rendered,
+        }
+    }
+}
+
+/// Strips escape sequences and any other control characters from a process' output.
+fn sanitize_output(line: &str) -> String {
+    let mut output = String::with_capacity(line.len());
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\t' => output.push_str("    "),
+            // Skip CSI and OSC sequences entirely so colors, cursor movements, hyperlinks and
+            // window titles don't leak into the slide.
+            '\x1b' => match chars.clone().next() {
+                Some('[') => {
+                    chars.next();
+                    for c in chars.by_ref() {
+                        if ('\x40'..='\x7e').contains(&c) {
+                            break;
+                        }
+                    }
+                }
+                Some(']') => {
+                    chars.next();
+                    // These end either with a BEL or with an `ESC \`.
+                    while let Some(c) = chars.next() {
+                        if c == '\x07' {
+                            break;
+                        }
+                        if c == '\x1b' && chars.clone().next() == Some('\\') {
+                            chars.next();
+                            break;
+                        }
+                    }
+                }
+                _ => (),
+            },
+            c if c.is_control() => (),
+            c => output.push(c),
+        }
+    }
+    output
+}
+
+pub(crate) enum Comment {
+    Pause,
+    EndSlide,
+    SpeakerNote(String),
+    ColumnLayout(Vec<u8>),
+    Column(usize),
+    ResetLayout,
+    JumpToMiddle,
+}
+
+pub(crate) enum CommentParseError {
+    /// This is just a regular comment.
+    NotACommand,
+
+    /// This is a command but its arguments are invalid.
+    InvalidArguments(&'static str),
+}
+
+impl FromStr for Comment {
+    type Err = CommentParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(note) = s.strip_prefix("speaker_note:") {
+            // Multi-line notes are usually indented so strip that away.
+            let note: Vec<_> = note.trim().lines().map(str::trim).collect();
+            return Ok(Self::SpeakerNote(note.join("\n")));
+        }
+        if let Some(columns) = s.strip_prefix("column_layout:") {
+            let columns =
+                serde_yaml::from_str(columns).map_err(|_| CommentParseError::InvalidArguments("column_layout"))?;
+            return Ok(Self::ColumnLayout(columns));
+        }
+        if let Some(column) = s.strip_prefix("column:") {
+            let column = column.trim().parse().map_err(|_| CommentParseError::InvalidArguments("column"))?;
+            return Ok(Self::Column(column));
+        }
+        match s {
+            "pause" => Ok(Self::Pause),
+            "end_slide" => Ok(Self::EndSlide),
+            "reset_layout" => Ok(Self::ResetLayout),
+            "jump_to_middle" => Ok(Self::JumpToMiddle),
+            _ => Err(CommentParseError::NotACommand),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        markdown::elements::CodeAttributes,
+        presentation::PreformattedLine,
+        theme::{
+            AlertStyle, AlertTypeStyle, BlockQuoteStyle, ListLevelStyle, NumberingStyle, TableBorders, TableStyle,
+        },
+    };
+    use crossterm::style::Color;
+    use rstest::rstest;
+    use std::time::Instant;
+
+    fn build_presentation_result(elements: Vec<MarkdownElement>) -> Result<Presentation, BuildError> {
+        let highlighter = CodeHighlighter::new("base16-ocean.dark").unwrap();
+        let theme = PresentationTheme::default();
+        let mut resources = Resources::new("/tmp");
+        PresentationBuilder::new(highlighter, &theme, &mut resources).build(elements)
+    }
+
+    fn build_presentation(elements: Vec<MarkdownElement>) -> Presentation {
+        build_presentation_result(elements).expect("build failed")
+    }
+
+    fn build_presentation_with_theme(elements: Vec<MarkdownElement>, theme: &PresentationTheme) -> Presentation {
+        let highlighter = CodeHighlighter::new("base16-ocean.dark").unwrap();
+        let mut resources = Resources::new("/tmp");
+        PresentationBuilder::new(highlighter, theme, &mut resources).build(elements).expect("build failed")
+    }
+
+    fn is_visible(operation: &RenderOperation) -> bool {
+        use RenderOperation::*;
+        match operation {
+            ClearScreen | SetColors(_) | JumpToVerticalCenter | JumpToMiddle | JumpToBottom | JumpToSlideBottom
+            | JumpToWindowBottom => false,
+            _ => true,
+        }
+    }
+
+    // Tables are generated dynamically so they're not part of the operations until they're rendered.
+    // The footer is generated dynamically too but it always comes after them.
+    fn generate_dynamic(slide: &Slide, columns: u16) -> Vec<RenderOperation> {
+        let dimensions = WindowSize { rows: 24, columns, width: 0, height: 0 };
+        let generator = slide
+            .render_operations
+            .iter()
+            .find_map(|operation| match operation {
+                RenderOperation::RenderDynamic(generator) => Some(generator),
+                _ => None,
+            })
+            .expect("no dynamic operation");
+        generator.as_render_operations(&dimensions)
+    }
+
+    fn extract_dynamic_lines(slide: &Slide, columns: u16) -> Vec<String> {
+        extract_text_lines(&generate_dynamic(slide, columns))
+    }
+
+    fn extract_text_lines(operations: &[RenderOperation]) -> Vec<String> {
+        let mut output = Vec::new();
+        for operation in operations {
+            match operation {
+                RenderOperation::RenderTextLine { line, .. } => {
+                    let texts: Vec<_> = line.iter_texts().map(|text| text.text.text.clone()).collect();
+                    output.push(texts.join(""));
+                }
+                _ => (),
+            };
+        }
+        output
+    }
+
+    #[test]
+    fn prelude_appears_once() {
+        let elements = vec![
+            MarkdownElement::FrontMatter("author: bob".to_string()),
+            MarkdownElement::Heading { text: Text::from("hello"), level: 1 },
+            MarkdownElement::Comment("end_slide".to_string()),
+            MarkdownElement::Heading { text: Text::from("bye"), level: 1 },
+        ];
+        let presentation = build_presentation(elements);
         for (index, slide) in presentation.iter_slides().into_iter().enumerate() {
             let clear_screen_count =
                 slide.render_operations.iter().filter(|op| matches!(op, RenderOperation::ClearScreen)).count();
@@ -643,10 +1670,12 @@ mod test {
     #[test]
     fn preformatted_blocks_account_for_unicode_widths() {
         let text = "苹果".to_string();
-        let elements = vec![
-            MarkdownElement::BlockQuote(vec![text.clone()]),
-            MarkdownElement::Code(Code { contents: text.clone(), language: ProgrammingLanguage::Unknown }),
-        ];
+        let elements = vec![MarkdownElement::Code(Code {
+            contents: text.clone(),
+            language: ProgrammingLanguage::Unknown,
+            language_name: "".into(),
+            attributes: Default::default(),
+        })];
         let presentation = build_presentation(elements);
         let slides = presentation.into_slides();
         let lengths: Vec<_> = slides[0]
@@ -659,10 +1688,143 @@ mod test {
                 _ => None,
             })
             .collect();
-        assert_eq!(lengths.len(), 2);
+        assert_eq!(lengths.len(), 1);
         let width = &text.width();
         assert_eq!(lengths[0], (width, width));
-        assert_eq!(lengths[1], (width, width));
+    }
+
+    fn build_block_quote(elements: Vec<MarkdownElement>) -> Slide {
+        let block_quote = BlockQuoteStyle {
+            alignment: Some(Alignment::Center { minimum_margin: 0, minimum_size: 0 }),
+            prefix: Some("> ".into()),
+            colors: Default::default(),
+        };
+        let theme = PresentationTheme { block_quote, ..Default::default() };
+        build_presentation_with_theme(vec![MarkdownElement::BlockQuote(elements)], &theme).into_slides().remove(0)
+    }
+
+    #[test]
+    fn block_quote_wrapping() {
+        let paragraph = MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("one two three"))]);
+        let list = MarkdownElement::List(vec![ListItem {
+            contents: Text::from("four five"),
+            depth: 0,
+            item_type: ListItemType::Unordered,
+            blocks: Vec::new(),
+            checkbox: None,
+        }]);
+        let nested = MarkdownElement::BlockQuote(vec![MarkdownElement::Paragraph(vec![ParagraphElement::Text(
+            Text::from("苹果"),
+        )])]);
+        let slide = build_block_quote(vec![paragraph, list, nested]);
+        // The prefix is in every wrapped line and lines are padded to the widest one.
+        let expected =
+            &["> one two ", "> three   ", ">         ", ">   • four", ">     five", ">         ", "> > 苹果  "];
+        assert_eq!(extract_dynamic_lines(&slide, 12), expected);
+    }
+
+    #[test]
+    fn alert() {
+        let note = AlertTypeStyle { title: None, icon: Some("!".into()), color: Some(Color::Blue) };
+        let alert = AlertStyle {
+            alignment: Some(Alignment::Center { minimum_margin: 0, minimum_size: 0 }),
+            note,
+            ..Default::default()
+        };
+        let theme = PresentationTheme { alert, ..Default::default() };
+        let paragraph = MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("one two three"))]);
+        let element = MarkdownElement::Alert { alert_type: AlertType::Note, title: None, elements: vec![paragraph] };
+        let slide = build_presentation_with_theme(vec![element], &theme).into_slides().remove(0);
+
+        let expected = &["┌─ ! Note ─┐", "│ one two  │", "│ three    │", "└──────────┘"];
+        assert_eq!(extract_dynamic_lines(&slide, 14), expected);
+
+        let operations = generate_dynamic(&slide, 14);
+        let RenderOperation::RenderTextLine { line, .. } = &operations[0] else { panic!("not a text line") };
+        let title = line.iter_texts().nth(1).expect("no title");
+        assert_eq!(
+            title.text.style,
+            TextStyle::default().colors(Colors { foreground: Some(Color::Blue), background: None }).bold()
+        );
+    }
+
+    #[test]
+    fn alert_prefix() {
+        let alert = AlertStyle { prefix: Some("| ".into()), ..Default::default() };
+        let block_quote = BlockQuoteStyle { prefix: Some("> ".into()), ..Default::default() };
+        let theme = PresentationTheme { alert, block_quote, ..Default::default() };
+        let paragraph = MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("hi"))]);
+        let element = MarkdownElement::Alert {
+            alert_type: AlertType::Tip,
+            title: None,
+            elements: vec![MarkdownElement::BlockQuote(vec![paragraph])],
+        };
+        let slide = build_presentation_with_theme(vec![element], &theme).into_slides().remove(0);
+        let expected = &["┌─ ☆ Tip ──────────┐", "│ | hi             │", "└──────────────────┘"];
+        assert_eq!(extract_dynamic_lines(&slide, 20), expected);
+
+        // Without a color in the theme the border uses the alert's colors.
+        let operations = generate_dynamic(&slide, 20);
+        let RenderOperation::RenderTextLine { line, .. } = &operations[0] else { panic!("not a text line") };
+        let border = line.iter_texts().next().expect("no border");
+        assert_eq!(border.text.style, TextStyle::default());
+    }
+
+    #[test]
+    fn block_quote_formatting() {
+        let text =
+            Text { chunks: vec![StyledText::new("bold", TextStyle::default().bold()), StyledText::from(" text")] };
+        let slide = build_block_quote(vec![MarkdownElement::Paragraph(vec![ParagraphElement::Text(text)])]);
+        let operations = generate_dynamic(&slide, 20);
+        let RenderOperation::RenderTextLine { line, .. } = &operations[0] else { panic!("not a text line") };
+        let chunks: Vec<_> = line.iter_texts().map(|text| text.text.clone()).collect();
+        assert_eq!(chunks[1], StyledText::new("bold", TextStyle::default().bold()));
+    }
+
+    #[test]
+    fn speaker_notes() {
+        let elements = vec![
+            MarkdownElement::Comment("speaker_note: hello".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("hi"))]),
+            MarkdownElement::Comment("speaker_note: first line\n    second line".into()),
+            MarkdownElement::Comment("end_slide".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("bye"))]),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        assert_eq!(slides.len(), 2);
+        assert_eq!(slides[0].notes, &["hello", "first line\nsecond line"]);
+        assert!(slides[1].notes.is_empty());
+
+        let lines = extract_text_lines(&slides[0].render_operations);
+        assert_eq!(lines, &["hi"]);
+    }
+
+    #[test]
+    fn speaker_notes_across_pauses() {
+        let elements = vec![
+            MarkdownElement::Comment("speaker_note: hello".into()),
+            MarkdownElement::Comment("pause".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("hi"))]),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        assert_eq!(slides.len(), 2);
+        for slide in slides {
+            assert_eq!(slide.notes, &["hello"]);
+        }
+    }
+
+    #[test]
+    fn slides_after_pause() {
+        let elements = vec![
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("hi"))]),
+            MarkdownElement::Comment("pause".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("there"))]),
+            MarkdownElement::Comment("end_slide".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("bye"))]),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        let after_pause: Vec<_> = slides.iter().map(|slide| slide.after_pause).collect();
+        assert_eq!(after_pause, &[false, true, false]);
     }
 
     #[test]
@@ -670,12 +1832,597 @@ mod test {
         let elements = vec![MarkdownElement::Table(Table {
             header: TableRow(vec![Text::from("key"), Text::from("value"), Text::from("other")]),
             rows: vec![TableRow(vec![Text::from("potato"), Text::from("bar"), Text::from("yes")])],
+            alignments: vec![ColumnAlignment::Left; 3],
         })];
         let slides = build_presentation(elements).into_slides();
-        let operations: Vec<_> =
-            slides.into_iter().next().unwrap().render_operations.into_iter().filter(|op| is_visible(op)).collect();
-        let lines = extract_text_lines(&operations);
+        let lines = extract_dynamic_lines(&slides[0], 80);
         let expected_lines = &["key    │ value │ other", "───────┼───────┼──────", "potato │ bar   │ yes  "];
         assert_eq!(lines, expected_lines);
     }
+
+    fn build_table(table: Table, style: TableStyle) -> Slide {
+        let theme = PresentationTheme { table: style, ..Default::default() };
+        build_presentation_with_theme(vec![MarkdownElement::Table(table)], &theme).into_slides().remove(0)
+    }
+
+    #[rstest]
+    #[case::no_borders(TableBorders::None, &["key     value", "potato  bar  "])]
+    #[case::full_borders(
+        TableBorders::Full,
+        &["┌────────┬───────┐", "│ key    │ value │", "├────────┼───────┤", "│ potato │ bar   │", "└────────┴───────┘"]
+    )]
+    fn table_borders(#[case] borders: TableBorders, #[case] expected: &[&str]) {
+        let table = Table {
+            header: TableRow(vec![Text::from("key"), Text::from("value")]),
+            rows: vec![TableRow(vec![Text::from("potato"), Text::from("bar")])],
+            alignments: vec![ColumnAlignment::Left; 2],
+        };
+        let slide = build_table(table, TableStyle { borders, ..Default::default() });
+        assert_eq!(extract_dynamic_lines(&slide, 80), expected);
+    }
+
+    #[test]
+    fn table_wrapping() {
+        let table = Table {
+            header: TableRow(vec![Text::from("a"), Text::from("b"), Text::from("c")]),
+            rows: vec![TableRow(vec![Text::from("one two three"), Text::from("x"), Text::from("four five")])],
+            alignments: vec![ColumnAlignment::Left, ColumnAlignment::Center, ColumnAlignment::Right],
+        };
+        let slide = build_table(table, Default::default());
+        // The widest columns are shrunk until the table fits.
+        let expected =
+            &["a     │ b │     c", "──────┼───┼──────", "one   │ x │  four", "two   │   │  five", "three │   │      "];
+        assert_eq!(extract_dynamic_lines(&slide, 17), expected);
+    }
+
+    #[test]
+    fn table_row_colors() {
+        let row = |text: &str| TableRow(vec![Text::from(text)]);
+        let table = Table { header: row("h"), rows: vec![row("a"), row("b"), row("c")], alignments: vec![] };
+        let colors = |color| Colors { foreground: Some(color), background: None };
+        let style = TableStyle {
+            header_colors: colors(Color::Red),
+            row_colors: colors(Color::Green),
+            alternate_row_colors: Some(colors(Color::Blue)),
+            ..Default::default()
+        };
+        let slide = build_table(table, style);
+        let foregrounds: Vec<_> = generate_dynamic(&slide, 80)
+            .into_iter()
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderTextLine { line, .. } => Some(line),
+                _ => None,
+            })
+            .map(|line| line.iter_texts().next().unwrap().text.style.colors.foreground)
+            .collect();
+        let expected = &[Some(Color::Red), None, Some(Color::Green), Some(Color::Blue), Some(Color::Green)];
+        assert_eq!(foregrounds, expected);
+    }
+
+    #[test]
+    fn executable_code() {
+        let code = Code {
+            contents: "echo hi".into(),
+            language: ProgrammingLanguage::Bash,
+            language_name: "bash".into(),
+            attributes: CodeAttributes { execute: true, ..Default::default() },
+        };
+        let slides = build_presentation(vec![MarkdownElement::Code(code)]).into_slides();
+        let operation = slides[0]
+            .render_operations
+            .iter()
+            .find_map(|op| match op {
+                RenderOperation::RenderOnDemand(operation) => Some(operation),
+                _ => None,
+            })
+            .expect("no on demand operation");
+        let dimensions = WindowSize { rows: 10, columns: 40, width: 320, height: 160 };
+        assert_eq!(operation.poll_state(), RenderOnDemandState::NotStarted);
+        assert!(operation.as_render_operations(&dimensions).is_empty());
+
+        assert!(operation.start_render());
+        assert!(!operation.start_render());
+        while operation.poll_state() != RenderOnDemandState::Rendered {
+            std::thread::sleep(std::time::Duration::from_millis(10));
+        }
+        let lines: Vec<_> = operation
+            .as_render_operations(&dimensions)
+            .into_iter()
+            .filter_map(|op| match op {
+                RenderOperation::RenderPreformattedLine(line) => {
+                    Some(line.text.into_iter().map(|chunk| chunk.text).collect::<String>())
+                }
+                _ => None,
+            })
+            .collect();
+        let expected =
+            &["╭─ output ───────────────────╮", "│ hi                         │", "╰─ finished ─────────────────╯"];
+        assert_eq!(lines, expected);
+    }
+
+    #[test]
+    fn highlight_groups() {
+        let groups =
+            vec![HighlightGroup::new(vec![Highlight::Single(1)]), HighlightGroup::new(vec![Highlight::Range(2..=3)])];
+        let code = Code {
+            contents: "a\nb\nc\n".into(),
+            language: ProgrammingLanguage::Unknown,
+            language_name: "".into(),
+            attributes: CodeAttributes { highlight_groups: groups, ..Default::default() },
+        };
+        let elements = vec![
+            MarkdownElement::Code(code),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("after"))]),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        assert_eq!(slides.len(), 2);
+
+        let highlighter = CodeHighlighter::new("base16-ocean.dark").unwrap();
+        let lines = highlighter.highlight("a\nb\nc\n", &ProgrammingLanguage::Unknown);
+        let highlighted: Vec<_> =
+            lines.iter().map(|line| PresentationBuilder::trim_code_line(&line.formatted)).collect();
+        let dimmed: Vec<_> = lines.iter().map(|line| PresentationBuilder::trim_code_line(&line.dimmed)).collect();
+        let expected_lines =
+            [vec![&highlighted[0], &dimmed[1], &dimmed[2]], vec![&dimmed[0], &highlighted[1], &highlighted[2]]];
+        for (slide, expected_lines) in slides.iter().zip(expected_lines) {
+            let lines: Vec<_> = slide
+                .render_operations
+                .iter()
+                .filter_map(|op| match op {
+                    RenderOperation::RenderPreformattedLine(line) => Some(&line.text),
+                    _ => None,
+                })
+                .collect();
+            assert_eq!(lines, expected_lines);
+        }
+        // The text after the code block only shows up after the last group.
+        assert!(extract_text_lines(&slides[0].render_operations).is_empty());
+        assert_eq!(extract_text_lines(&slides[1].render_operations), &["after"]);
+    }
+
+    #[test]
+    fn line_numbers() {
+        let code = Code {
+            contents: (1..=10).map(|number| format!("{number}\n")).collect(),
+            language: ProgrammingLanguage::Unknown,
+            language_name: "".into(),
+            attributes: CodeAttributes { line_numbers: true, ..Default::default() },
+        };
+        let slides = build_presentation(vec![MarkdownElement::Code(code)]).into_slides();
+        let lines: Vec<_> = slides[0]
+            .render_operations
+            .iter()
+            .filter_map(|op| match op {
+                RenderOperation::RenderPreformattedLine(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(lines.len(), 10);
+        for (index, line) in lines.into_iter().enumerate() {
+            let number = index + 1;
+            let expected_prefix = format!("{number:>2} ");
+            assert_eq!(line.text[0].text, expected_prefix);
+            assert_eq!(line.unformatted_length, 3 + number.to_string().len());
+            assert_eq!(line.block_length, 5);
+        }
+    }
+
+    #[test]
+    fn column_layout() {
+        let elements = vec![
+            MarkdownElement::Comment("column_layout: [2, 1]".into()),
+            MarkdownElement::Comment("column: 0".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("left"))]),
+            MarkdownElement::Comment("column: 1".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("right"))]),
+            MarkdownElement::Comment("reset_layout".into()),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        let operations: Vec<_> = slides[0]
+            .render_operations
+            .iter()
+            .filter(|op| {
+                matches!(
+                    op,
+                    RenderOperation::InitColumnLayout { .. }
+                        | RenderOperation::EnterColumn { .. }
+                        | RenderOperation::ExitLayout
+                )
+            })
+            .collect();
+        assert!(matches!(
+            operations.as_slice(),
+            [
+                RenderOperation::InitColumnLayout { columns },
+                RenderOperation::EnterColumn { column: 0 },
+                RenderOperation::EnterColumn { column: 1 },
+                RenderOperation::ExitLayout,
+            ] if columns == &[2, 1]
+        ));
+    }
+
+    #[test]
+    fn layout_reset_at_end_of_slide() {
+        let elements = vec![
+            MarkdownElement::Comment("column_layout: [1, 1]".into()),
+            MarkdownElement::Comment("column: 0".into()),
+            MarkdownElement::Comment("pause".into()),
+            MarkdownElement::Comment("column: 1".into()),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        assert_eq!(slides.len(), 2);
+        for slide in slides {
+            let exits = slide.render_operations.iter().filter(|op| matches!(op, RenderOperation::ExitLayout)).count();
+            assert_eq!(exits, 1);
+        }
+    }
+
+    #[test]
+    fn jump_to_middle() {
+        let elements = vec![
+            MarkdownElement::Comment("jump_to_middle".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("hi"))]),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        let operations = &slides[0].render_operations;
+        let index = operations.iter().position(|op| matches!(op, RenderOperation::JumpToMiddle)).expect("no jump");
+        // The comment itself shouldn't introduce an empty line before the text.
+        assert!(matches!(operations[index + 1], RenderOperation::RenderTextLine { .. }));
+    }
+
+    #[test]
+    fn jump_to_middle_option() {
+        let elements = vec![
+            MarkdownElement::FrontMatter("options:\n  jump_to_middle: true".into()),
+            MarkdownElement::Comment("end_slide".into()),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        assert_eq!(slides.len(), 2);
+        for slide in slides {
+            assert!(slide.render_operations.iter().any(|op| matches!(op, RenderOperation::JumpToMiddle)));
+        }
+    }
+
+    fn render_footer(template: &str, current_slide: usize, elapsed: Duration) -> (String, Colors) {
+        let clock = PresentationClock::default();
+        clock.start(Instant::now().checked_sub(elapsed).expect("clock too early"));
+        let context =
+            FooterContext { total_slides: 2, author: "me".into(), duration: Some(Duration::from_secs(600)), clock };
+        let style = FooterStyle::Template {
+            left: Some(template.into()),
+            right: None,
+            colors: Colors::default(),
+            warning_colors: None,
+        };
+        let generator = FooterGenerator { current_slide, context: Rc::new(RefCell::new(context)), style };
+        assert!(generator.is_time_dependent());
+
+        let operations = generator.as_render_operations(&WindowSize { rows: 10, columns: 80, width: 0, height: 0 });
+        let RenderOperation::RenderTextLine { line, .. } = &operations[1] else { panic!("not a text line") };
+        let text = line.iter_texts().next().expect("no text").text.clone();
+        (text.text, text.style.colors)
+    }
+
+    #[test]
+    fn footer_timer() {
+        let (text, colors) = render_footer("{author} {elapsed} {remaining}", 0, Duration::from_secs(65));
+        assert_eq!(text, "me 00:01:05 00:08:55");
+        assert_eq!(colors, Colors::default());
+    }
+
+    #[test]
+    fn footer_behind_schedule() {
+        // Each of the 2 slides is expected to take 5 minutes.
+        let (_, colors) = render_footer("{current_slide}", 0, Duration::from_secs(301));
+        assert_eq!(colors.foreground, Some(Color::Red));
+
+        let (_, colors) = render_footer("{current_slide}", 1, Duration::from_secs(301));
+        assert_eq!(colors, Colors::default());
+    }
+
+    #[rstest]
+    #[case::seconds("45s", Some(45))]
+    #[case::minutes("20m", Some(1200))]
+    #[case::mixed("1h30m15s", Some(5415))]
+    #[case::no_unit("20", None)]
+    #[case::unknown_unit("20d", None)]
+    #[case::empty("", None)]
+    #[case::overflow("18446744073709551615h", None)]
+    fn durations(#[case] input: &str, #[case] expected: Option<u64>) {
+        assert_eq!(parse_duration(input), expected.map(Duration::from_secs));
+    }
+
+    #[test]
+    fn invalid_duration() {
+        let result = build_presentation_result(vec![MarkdownElement::FrontMatter("duration: potato".into())]);
+        assert!(matches!(result, Err(BuildError::InvalidMetadata(_))));
+    }
+
+    #[test]
+    fn footnotes() {
+        let paragraph =
+            |chunks: Vec<StyledText>| MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text { chunks })]);
+        let footnote =
+            |name: &str, text: &str| MarkdownElement::FootnoteDefinition { name: name.into(), text: Text::from(text) };
+        let elements = vec![
+            paragraph(vec![StyledText::from("a"), StyledText::footnote_reference("second")]),
+            MarkdownElement::Comment("pause".into()),
+            paragraph(vec![StyledText::from("b"), StyledText::footnote_reference("first")]),
+            MarkdownElement::Comment("end_slide".into()),
+            paragraph(vec![StyledText::from("c")]),
+            footnote("first", "first note"),
+            footnote("second", "second note"),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        let lines: Vec<_> = slides.iter().map(|slide| extract_text_lines(&slide.render_operations)).collect();
+        assert_eq!(lines[0], &["a²", "² second note"]);
+        assert_eq!(lines[1], &["a²", "b¹", "¹ first note", "² second note"]);
+        assert_eq!(lines[2], &["c"]);
+    }
+
+    #[test]
+    fn list_item_blocks() {
+        let item = |text: &str, depth, item_type, blocks| ListItem {
+            contents: Text::from(text),
+            depth,
+            item_type,
+            blocks,
+            checkbox: None,
+        };
+        let code = Code {
+            contents: "echo hi".into(),
+            language: ProgrammingLanguage::Bash,
+            language_name: "bash".into(),
+            attributes: Default::default(),
+        };
+        let elements = vec![MarkdownElement::List(vec![
+            item("one", 0, ListItemType::OrderedPeriod(1), vec![MarkdownElement::Code(code)]),
+            item("sub", 1, ListItemType::Unordered, vec![]),
+            item("after", 0, ListItemType::Continuation, vec![]),
+        ])];
+        let mut theme = PresentationTheme::default();
+        theme.default_style.alignment = Some(Alignment::Left { margin: 1 });
+
+        let presentation = build_presentation_with_theme(elements, &theme);
+        let slide = presentation.iter_slides().next().unwrap();
+        let lines = extract_text_lines(&slide.render_operations);
+        assert_eq!(lines, &["  1.  one", "    ◦ sub", "      after"]);
+
+        // The code is aligned with the text of the item it's in.
+        let code_alignments: Vec<_> = slide
+            .render_operations
+            .iter()
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderPreformattedLine(line) => Some(line.alignment.clone()),
+                _ => None,
+            })
+            .collect();
+        assert!(!code_alignments.is_empty());
+        assert!(code_alignments.iter().all(|alignment| alignment == &Alignment::Left { margin: 7 }));
+
+        // An item that only contains a nested list, like `* * nested`, has no text to align with.
+        let elements = vec![MarkdownElement::List(vec![
+            item("nested", 1, ListItemType::Unordered, vec![]),
+            item("after", 0, ListItemType::Continuation, vec![]),
+        ])];
+        let presentation = build_presentation_with_theme(elements, &theme);
+        let slide = presentation.iter_slides().next().unwrap();
+        let lines = extract_text_lines(&slide.render_operations);
+        assert_eq!(lines, &["    ◦ nested", "    after"]);
+    }
+
+    #[test]
+    fn list_styles() {
+        let item = |text: &str, depth, item_type| ListItem {
+            contents: Text::from(text),
+            depth,
+            item_type,
+            blocks: vec![],
+            checkbox: None,
+        };
+        let elements = vec![MarkdownElement::List(vec![
+            item("one", 0, ListItemType::OrderedPeriod(4)),
+            item("two", 1, ListItemType::Unordered),
+            item("three", 2, ListItemType::OrderedParens(2)),
+        ])];
+        let mut theme = PresentationTheme::default();
+        theme.list.indentation = Some(1);
+        theme.list.levels = vec![
+            ListLevelStyle { numbering: NumberingStyle::LowerRoman, ..Default::default() },
+            ListLevelStyle { bullet: Some("-".into()), numbering: NumberingStyle::UpperAlpha, ..Default::default() },
+        ];
+
+        let presentation = build_presentation_with_theme(elements, &theme);
+        let slide = presentation.iter_slides().next().unwrap();
+        let lines = extract_text_lines(&slide.render_operations);
+        // Levels deeper than the ones in the theme use the last one.
+        assert_eq!(lines, &[" iv.  one", "  - two", "   B)  three"]);
+    }
+
+    #[test]
+    fn task_list() {
+        let item = |text: &str, item_type, checked| ListItem {
+            contents: Text::from(text),
+            depth: 0,
+            item_type,
+            blocks: Vec::new(),
+            checkbox: Some(checked),
+        };
+        let elements = vec![MarkdownElement::List(vec![
+            item("done", ListItemType::Unordered, true),
+            item("pending", ListItemType::Unordered, false),
+            item("numbered", ListItemType::OrderedParens(3), true),
+        ])];
+        let mut theme = PresentationTheme::default();
+        theme.task_list.checked = Some('x');
+
+        let presentation = build_presentation_with_theme(elements, &theme);
+        let slide = presentation.iter_slides().next().unwrap();
+        let lines = extract_text_lines(&slide.render_operations);
+        assert_eq!(lines, &["  • x done", "  • ☐ pending", "  3) x numbered"]);
+    }
+
+    #[test]
+    fn palette_classes() {
+        let mut theme = PresentationTheme::default();
+        let colors = Colors { foreground: Some(Color::Red), background: None };
+        theme.palette.classes.insert("red".into(), colors.clone());
+        let mut text = StyledText::from("hi");
+        text.class = Some("red".into());
+        let elements = vec![MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text { chunks: vec![text] })])];
+
+        let presentation = build_presentation_with_theme(elements.clone(), &theme);
+        let line = presentation.iter_slides().flat_map(|slide| &slide.render_operations).find_map(|op| match op {
+            RenderOperation::RenderTextLine { line, .. } => Some(line),
+            _ => None,
+        });
+        let style = &line.expect("no text").iter_texts().next().expect("no chunks").text.style;
+        assert_eq!(style.colors, colors);
+
+        let mut text = StyledText::new(
+            "hi",
+            TextStyle::default().colors(Colors { foreground: Some(Color::Blue), background: None }),
+        );
+        text.class = Some("red".into());
+        let overridden = vec![MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text { chunks: vec![text] })])];
+        let presentation = build_presentation_with_theme(overridden, &theme);
+        let line = presentation.iter_slides().flat_map(|slide| &slide.render_operations).find_map(|op| match op {
+            RenderOperation::RenderTextLine { line, .. } => Some(line),
+            _ => None,
+        });
+        let style = &line.expect("no text").iter_texts().next().expect("no chunks").text.style;
+        assert_eq!(style.colors.foreground, Some(Color::Blue));
+
+        let result = build_presentation_result(elements);
+        assert!(matches!(result, Err(BuildError::UndefinedClass(_))), "{:?}", result.err());
+    }
+
+    #[test]
+    fn variables() {
+        let front_matter = "event: RustConf\nvars:\n  talk: Rust at scale";
+        let text = Text {
+            chunks: vec![
+                StyledText::from("{{ talk }} @ {{event}} "),
+                StyledText::new("{{event}}", TextStyle::default().code()),
+            ],
+        };
+        let elements = vec![
+            MarkdownElement::FrontMatter(front_matter.into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(text)]),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        let lines = extract_text_lines(&slides[0].render_operations);
+        assert_eq!(lines, &["Rust at scale @ RustConf {{event}}"]);
+
+        let footer = extract_dynamic_lines(&slides[0], 80);
+        assert_eq!(footer, &["1 / 1"]);
+    }
+
+    #[test]
+    fn footer_variables() {
+        let footer = FooterStyle::Template {
+            left: Some("{{event}}, {{date}}".into()),
+            right: None,
+            colors: Default::default(),
+            warning_colors: None,
+        };
+        let theme = PresentationTheme { footer, ..Default::default() };
+        let elements = vec![MarkdownElement::FrontMatter("event: RustConf\ndate: 2023-09-12".into())];
+        let presentation = build_presentation_with_theme(elements, &theme);
+        let footer = extract_dynamic_lines(&presentation.into_slides()[0], 80);
+        assert_eq!(footer, &["RustConf, 2023-09-12"]);
+    }
+
+    #[test]
+    fn undefined_variable() {
+        let text = Text::from("{{potato}} {{ and }} {{");
+        let elements = vec![MarkdownElement::Paragraph(vec![ParagraphElement::Text(text)])];
+        let slides = build_presentation(elements).into_slides();
+        let lines = extract_text_lines(&slides[0].render_operations);
+        assert_eq!(lines, &["{{potato}} {{ and }} {{"]);
+    }
+
+    #[test]
+    fn escaped_variables() {
+        let text = Text::from("{{{{event}} at {{event}}");
+        let elements = vec![
+            MarkdownElement::FrontMatter("event: RustConf".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(text)]),
+        ];
+        let slides = build_presentation(elements).into_slides();
+        let lines = extract_text_lines(&slides[0].render_operations);
+        assert_eq!(lines, &["{{event}} at RustConf"]);
+    }
+
+    #[test]
+    fn links_with_urls() {
+        let mut theme = PresentationTheme::default();
+        theme.link.mode = LinkMode::LabelAndUrl;
+        let link = TextStyle::default().link("https://example.com");
+        let chunks = vec![
+            StyledText::from("see "),
+            StyledText::new("my", link.clone().bold()),
+            StyledText::new(" site", link),
+            StyledText::from(" or "),
+            StyledText::new("https://a.com", TextStyle::default().link("https://a.com")),
+        ];
+        let elements = vec![MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text { chunks })])];
+
+        let presentation = build_presentation_with_theme(elements, &theme);
+        let slide = presentation.iter_slides().next().unwrap();
+        let lines = extract_text_lines(&slide.render_operations);
+        assert_eq!(lines, &["see my site (https://example.com) or https://a.com"]);
+    }
+
+    #[rstest]
+    #[case::column_without_layout(&["column: 0"])]
+    #[case::column_out_of_bounds(&["column_layout: [1, 1]", "column: 2"])]
+    #[case::zero_width_column(&["column_layout: [1, 0]"])]
+    #[case::no_columns(&["column_layout: []"])]
+    fn invalid_layouts(#[case] comments: &[&str]) {
+        let elements = comments.iter().map(|comment| MarkdownElement::Comment(comment.to_string())).collect();
+        let result = build_presentation_result(elements);
+        assert!(matches!(result, Err(BuildError::InvalidLayout(_))), "{:?}", result.err());
+    }
+
+    #[test]
+    fn element_outside_column() {
+        let elements = vec![
+            MarkdownElement::Comment("column_layout: [1, 1]".into()),
+            MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("hi"))]),
+        ];
+        let result = build_presentation_result(elements);
+        assert!(matches!(result, Err(BuildError::InvalidLayout(_))));
+    }
+
+    #[rstest]
+    #[case::layout("column_layout: potato")]
+    #[case::column("column: potato")]
+    fn invalid_layout_arguments(#[case] comment: &str) {
+        let result = build_presentation_result(vec![MarkdownElement::Comment(comment.into())]);
+        assert!(matches!(result, Err(BuildError::InvalidCommentArguments(_))));
+    }
+
+    #[test]
+    fn unsupported_executable_code() {
+        let code = Code {
+            contents: "fn main() {}".into(),
+            language: ProgrammingLanguage::Rust,
+            language_name: "rust".into(),
+            attributes: CodeAttributes { execute: true, ..Default::default() },
+        };
+        let result = build_presentation_result(vec![MarkdownElement::Code(code)]);
+        assert!(matches!(result, Err(BuildError::UnsupportedExecution(ProgrammingLanguage::Rust))));
+    }
+
+    #[rstest]
+    #[case::plain("hello", "hello")]
+    #[case::tabs("a\tb", "a    b")]
+    #[case::colors("\x1b[1;31merror\x1b[0m: oops", "error: oops")]
+    #[case::control("a\rb\x07", "ab")]
+    #[case::hyperlink("\x1b]8;;https://example.com\x1b\\file\x1b]8;;\x1b\\ done", "file done")]
+    #[case::title("\x1b]0;my title\x07hi", "hi")]
+    fn output_sanitization(#[case] input: &str, #[case] expected: &str) {
+        assert_eq!(sanitize_output(input), expected);
+    }
 }
@@ -0,0 +1,210 @@
+use crate::{
+    builder::{BuildError, Comment, CommentParseError},
+    export::SlideRenderer,
+    loader::{LoadPresentationError, PresentationLoader},
+    markdown::{
+        elements::{MarkdownElement, ProgrammingLanguage},
+        parse::ParseError,
+    },
+    render::{draw::RenderError, properties::WindowSize},
+    resource::LoadImageError,
+};
+use std::{fs, io, path::Path};
+
+/// Checks presentations for problems without displaying them.
+pub struct PresentationChecker<'a> {
+    loader: PresentationLoader<'a>,
+    renderer: SlideRenderer,
+}
+
+impl<'a> PresentationChecker<'a> {
+    /// Construct a new checker.
+    ///
+    /// Slides will be checked to fit in a window of the given dimensions.
+    pub fn new(loader: PresentationLoader<'a>, dimensions: WindowSize) -> Self {
+        Self { loader, renderer: SlideRenderer::new(dimensions) }
+    }
+
+    /// Check the presentation in the given path and return all the issues found in it.
+    pub fn check(&mut self, path: &Path) -> io::Result<Vec<Issue>> {
+        let content = fs::read_to_string(path)?;
+        Ok(self.check_contents(&content, path))
+    }
+
+    fn check_contents(&mut self, contents: &str, path: &Path) -> Vec<Issue> {
+        let elements = match self.loader.load_elements(contents, path) {
+            Ok((elements, _)) => elements,
+            Err(LoadPresentationError::Parse(e)) => return vec![Issue::Parse(e)],
+            Err(e) => return vec![Issue::Include(e)],
+        };
+        let mut issues = Vec::new();
+        for element in &elements {
+            self.check_element(element, &mut issues);
+        }
+        // Building would fail on the first broken image so there's nothing else to learn.
+        if issues.iter().any(|issue| matches!(issue, Issue::Image(..))) {
+            return issues;
+        }
+
+        let presentation = match self.loader.build(elements) {
+            Ok(presentation) => presentation,
+            Err(e) => {
+                issues.push(Issue::Build(e));
+                return issues;
+            }
+        };
+        for (index, slide) in presentation.iter_slides().enumerate() {
+            let slide_number = index + 1;
+            match self.renderer.render_with_overflow(slide) {
+                Ok((_, overflow)) if overflow.rows > 0 => {
+                    issues.push(Issue::Overflow { slide: slide_number, rows: overflow.rows });
+                }
+                Ok(_) => (),
+                Err(error) => issues.push(Issue::Render { slide: slide_number, error }),
+            };
+        }
+        issues
+    }
+
+    fn check_element(&mut self, element: &MarkdownElement, issues: &mut Vec<Issue>) {
+        match element {
+            MarkdownElement::Comment(comment)
+                if matches!(comment.parse::<Comment>(), Err(CommentParseError::NotACommand))
+                    && Self::looks_like_command(comment) =>
+            {
+                issues.push(Issue::UnknownCommand(comment.trim().into()));
+            }
+            MarkdownElement::Image(path) => {
+                if let Err(e) = self.loader.resources().image(path) {
+                    issues.push(Issue::Image(path.display().to_string(), e));
+                }
+            }
+            // Code blocks without a language are plain text on purpose.
+            MarkdownElement::Code(code)
+                if code.language == ProgrammingLanguage::Unknown && !code.language_name.is_empty() =>
+            {
+                issues.push(Issue::UnknownLanguage(code.language_name.clone()));
+            }
+            MarkdownElement::List(items) => {
+                for element in items.iter().flat_map(|item| &item.blocks) {
+                    self.check_element(element, issues);
+                }
+            }
+            MarkdownElement::BlockQuote(elements) | MarkdownElement::Alert { elements, .. } => {
+                for element in elements {
+                    self.check_element(element, issues);
+                }
+            }
+            _ => (),
+        };
+    }
+
+    // Regular comments are allowed so only complain about the ones that are probably a typo, like
+    // `<!-- puase -->` or `<!-- speakr_note: hi -->`.
+    fn looks_like_command(comment: &str) -> bool {
+        let name = comment.split_once(':').map(|(name, _)| name).unwrap_or(comment).trim();
+        !name.is_empty() && name.chars().all(|c| c.is_ascii_lowercase() || c == '_')
+    }
+}
+
+/// An issue found in a presentation.
+#[derive(thiserror::Error, Debug)]
+pub enum Issue {
+    #[error(transparent)]
+    Parse(ParseError),
+
+    #[error(transparent)]
+    Include(LoadPresentationError),
+
+    #[error("unknown command in comment: '{0}'")]
+    UnknownCommand(String),
+
+    #[error("image '{0}' can't be loaded: {1}")]
+    Image(String, LoadImageError),
+
+    #[error("code block has an unknown language '{0}' and won't be highlighted")]
+    UnknownLanguage(String),
+
+    #[error(transparent)]
+    Build(BuildError),
+
+    #[error("slide {slide} overflows the screen by {rows} rows")]
+    Overflow { slide: usize, rows: u16 },
+
+    #[error("slide {slide} can't be rendered: {error}")]
+    Render { slide: usize, error: RenderError },
+}
+
+impl Issue {
+    /// Whether this issue is only a warning, meaning the presentation can still be displayed as
+    /// intended.
+    pub fn is_warning(&self) -> bool {
+        matches!(self, Self::UnknownLanguage(_))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        markdown::parse::MarkdownParser, render::highlighting::CodeHighlighter, resource::Resources,
+        theme::PresentationTheme,
+    };
+    use comrak::Arena;
+    use rstest::rstest;
+
+    fn check(contents: &str, dimensions: WindowSize) -> Vec<Issue> {
+        let arena = Arena::new();
+        let theme = PresentationTheme::default();
+        let highlighter = CodeHighlighter::new("base16-ocean.dark").unwrap();
+        let resources = Resources::new("/tmp");
+        let parser = MarkdownParser::new(&arena);
+        let loader = PresentationLoader::new(&theme, highlighter, parser, resources);
+        let mut checker = PresentationChecker::new(loader, dimensions);
+        checker.check_contents(contents, Path::new("/tmp/presentation.md"))
+    }
+
+    fn default_dimensions() -> WindowSize {
+        WindowSize { rows: 24, columns: 80, width: 640, height: 384 }
+    }
+
+    #[test]
+    fn no_issues() {
+        let issues = check(
+            "# hi\n\n<!-- pause -->\n\nbye\n\n<!-- just a note -->\n\n```rust\nlet a = 1;\n```",
+            default_dimensions(),
+        );
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[rstest]
+    #[case::unknown_command("<!-- puase -->", |issue: &Issue| matches!(issue, Issue::UnknownCommand(_)))]
+    #[case::unknown_language("```potato\nhi\n```", |issue: &Issue| matches!(issue, Issue::UnknownLanguage(name) if name == "potato"))]
+    #[case::missing_image("![](not-a-real-image.png)", |issue: &Issue| matches!(issue, Issue::Image(..)))]
+    #[case::missing_image_in_list("* hi\n\n  ![](not-a-real-image.png)", |issue: &Issue| matches!(issue, Issue::Image(..)))]
+    #[case::missing_image_in_quote("> * hi\n>\n>   ![](not-a-real-image.png)", |issue: &Issue| matches!(issue, Issue::Image(..)))]
+    #[case::unknown_language_in_alert("> [!note]\n> ```potato\n> hi\n> ```", |issue: &Issue| matches!(issue, Issue::UnknownLanguage(_)))]
+    #[case::unknown_language_in_list("* hi\n\n  ```potato\n  hi\n  ```", |issue: &Issue| matches!(issue, Issue::UnknownLanguage(_)))]
+    #[case::parse_error("<div>hi</div>", |issue: &Issue| matches!(issue, Issue::Parse(_)))]
+    #[case::build_error("<!-- column: 0 -->", |issue: &Issue| matches!(issue, Issue::Build(_)))]
+    #[case::missing_include("<!-- include: not-a-real-file.md -->", |issue: &Issue| matches!(issue, Issue::Include(_)))]
+    fn issues(#[case] input: &str, #[case] matcher: fn(&Issue) -> bool) {
+        let issues = check(input, default_dimensions());
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(matcher(&issues[0]), "{issues:?}");
+    }
+
+    #[test]
+    fn code_without_language() {
+        let issues = check("```\nhi\n```", default_dimensions());
+        assert!(issues.is_empty(), "{issues:?}");
+    }
+
+    #[test]
+    fn overflowing_slide() {
+        let input = "a\n\nb\n\nc\n\n<!-- end_slide -->\n\nd";
+        let issues = check(input, WindowSize { rows: 8, columns: 20, width: 160, height: 128 });
+        assert_eq!(issues.len(), 1, "{issues:?}");
+        assert!(matches!(issues[0], Issue::Overflow { slide: 1, rows: 1 }), "{issues:?}");
+    }
+}
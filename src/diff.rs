@@ -59,6 +59,10 @@ impl ContentDiff for RenderOperation {
                 false
             }
             (RenderImage(original), RenderImage(updated)) if original != updated => true,
+            (InitColumnLayout { columns: original }, InitColumnLayout { columns: updated }) if original != updated => {
+                true
+            }
+            (EnterColumn { column: original }, EnterColumn { column: updated }) if original != updated => true,
             (RenderPreformattedLine(original), RenderPreformattedLine(updated)) if original != updated => true,
             // This is only used for footers which are global. Ignore for now.
             (RenderDynamic(_), RenderDynamic(_)) => false,
@@ -89,6 +93,7 @@ where
 mod test {
     use super::*;
     use crate::{
+        markdown::elements::StyledText,
         presentation::{AsRenderOperations, PreformattedLine},
         render::properties::WindowSize,
         theme::{Alignment, Colors},
@@ -109,6 +114,8 @@ mod test {
     #[rstest]
     #[case(RenderOperation::ClearScreen)]
     #[case(RenderOperation::JumpToVerticalCenter)]
+    #[case(RenderOperation::JumpToMiddle)]
+    #[case(RenderOperation::JumpToBottom)]
     #[case(RenderOperation::JumpToSlideBottom)]
     #[case(RenderOperation::JumpToWindowBottom)]
     #[case(RenderOperation::RenderSeparator)]
@@ -117,18 +124,28 @@ mod test {
     #[case(RenderOperation::RenderTextLine{line: String::from("asd").into(), alignment: Default::default()})]
     #[case(RenderOperation::RenderPreformattedLine(
         PreformattedLine{
-            text: "asd".into(),
+            text: vec![StyledText::from("asd")],
             alignment: Default::default(),
             block_length: 42,
             unformatted_length: 1337
         }
     ))]
     #[case(RenderOperation::RenderDynamic(Rc::new(Dynamic)))]
+    #[case(RenderOperation::InitColumnLayout{ columns: vec![1, 2] })]
+    #[case(RenderOperation::EnterColumn{ column: 1 })]
+    #[case(RenderOperation::ExitLayout)]
     fn same_not_modified(#[case] operation: RenderOperation) {
         let diff = operation.is_content_different(&operation);
         assert!(!diff);
     }
 
+    #[test]
+    fn different_column_layout() {
+        let lhs = RenderOperation::InitColumnLayout { columns: vec![1, 2] };
+        let rhs = RenderOperation::InitColumnLayout { columns: vec![1, 3] };
+        assert!(lhs.is_content_different(&rhs));
+    }
+
     #[test]
     fn different_text() {
         let lhs = RenderOperation::RenderTextLine { line: String::from("foo").into(), alignment: Default::default() };
@@ -159,9 +176,9 @@ mod test {
     #[test]
     fn no_slide_changes() {
         let presentation = Presentation::new(vec![
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
         ]);
         assert_eq!(PresentationDiffer::first_modified_slide(&presentation, &presentation), None);
     }
@@ -169,20 +186,28 @@ mod test {
     #[test]
     fn slides_truncated() {
         let lhs = Presentation::new(vec![
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
         ]);
-        let rhs = Presentation::new(vec![Slide { render_operations: vec![RenderOperation::ClearScreen] }]);
+        let rhs = Presentation::new(vec![Slide {
+            render_operations: vec![RenderOperation::ClearScreen],
+            notes: Vec::new(),
+            after_pause: false,
+        }]);
 
         assert_eq!(PresentationDiffer::first_modified_slide(&lhs, &rhs), Some(0));
     }
 
     #[test]
     fn slides_added() {
-        let lhs = Presentation::new(vec![Slide { render_operations: vec![RenderOperation::ClearScreen] }]);
+        let lhs = Presentation::new(vec![Slide {
+            render_operations: vec![RenderOperation::ClearScreen],
+            notes: Vec::new(),
+            after_pause: false,
+        }]);
         let rhs = Presentation::new(vec![
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
         ]);
 
         assert_eq!(PresentationDiffer::first_modified_slide(&lhs, &rhs), Some(1));
@@ -191,14 +216,18 @@ mod test {
     #[test]
     fn second_slide_content_changed() {
         let lhs = Presentation::new(vec![
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
         ]);
         let rhs = Presentation::new(vec![
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
-            Slide { render_operations: vec![RenderOperation::JumpToVerticalCenter] },
-            Slide { render_operations: vec![RenderOperation::ClearScreen] },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
+            Slide {
+                render_operations: vec![RenderOperation::JumpToVerticalCenter],
+                notes: Vec::new(),
+                after_pause: false,
+            },
+            Slide { render_operations: vec![RenderOperation::ClearScreen], notes: Vec::new(), after_pause: false },
         ]);
 
         assert_eq!(PresentationDiffer::first_modified_slide(&lhs, &rhs), Some(1));
@@ -211,12 +240,16 @@ mod test {
                 background: None,
                 foreground: Some(Color::Red),
             })],
+            notes: Vec::new(),
+            after_pause: false,
         }]);
         let rhs = Presentation::new(vec![Slide {
             render_operations: vec![RenderOperation::SetColors(Colors {
                 background: None,
                 foreground: Some(Color::Black),
             })],
+            notes: Vec::new(),
+            after_pause: false,
         }]);
 
         assert_eq!(PresentationDiffer::first_modified_slide(&lhs, &rhs), None);
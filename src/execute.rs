@@ -0,0 +1,180 @@
+use crate::markdown::elements::{Code, ProgrammingLanguage};
+use std::{
+    io::{self, BufRead, BufReader, Read},
+    process::{Command, Stdio},
+    sync::{Arc, Mutex},
+    thread,
+};
+
+/// Allows executing code.
+pub struct CodeExecuter;
+
+impl CodeExecuter {
+    /// Checks whether code written in the given language can be executed.
+    pub fn is_execution_supported(language: &ProgrammingLanguage) -> bool {
+        Self::interpreter(language).is_some()
+    }
+
+    /// Execute a piece of code.
+    ///
+    /// This spawns a process that runs the code in the background. Its output can be polled via
+    /// the returned handle.
+    pub fn execute(code: &Code) -> Result<ExecutionHandle, CodeExecuteError> {
+        let (program, flag) = Self::interpreter(&code.language)
+            .ok_or_else(|| CodeExecuteError::UnsupportedLanguage(code.language.clone()))?;
+        let mut child = Command::new(program)
+            .arg(flag)
+            .arg(&code.contents)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| CodeExecuteError::SpawnProcess(program, e))?;
+
+        let state: Arc<Mutex<ExecutionState>> = Default::default();
+        let readers = [
+            child.stdout.take().map(|stdout| Self::spawn_reader(stdout, state.clone())),
+            child.stderr.take().map(|stderr| Self::spawn_reader(stderr, state.clone())),
+        ];
+        let handle = ExecutionHandle { state: state.clone() };
+        thread::spawn(move || {
+            for reader in readers.into_iter().flatten() {
+                let _ = reader.join();
+            }
+            let status = match child.wait() {
+                Ok(status) if status.success() => ProcessStatus::Success,
+                Ok(status) => ProcessStatus::Failure(status.code()),
+                Err(_) => ProcessStatus::Failure(None),
+            };
+            state.lock().unwrap().status = status;
+        });
+        Ok(handle)
+    }
+
+    fn spawn_reader<R: Read + Send + 'static>(source: R, state: Arc<Mutex<ExecutionState>>) -> thread::JoinHandle<()> {
+        thread::spawn(move || {
+            for line in BufReader::new(source).lines() {
+                let Ok(line) = line else {
+                    break;
+                };
+                state.lock().unwrap().output.push(line);
+            }
+        })
+    }
+
+    fn interpreter(language: &ProgrammingLanguage) -> Option<(&'static str, &'static str)> {
+        use ProgrammingLanguage::*;
+        let interpreter = match language {
+            Bash => ("bash", "-c"),
+            Shell => ("sh", "-c"),
+            Python => ("python3", "-c"),
+            Perl => ("perl", "-e"),
+            Lua => ("lua", "-e"),
+            JavaScript => ("node", "-e"),
+            _ => return None,
+        };
+        Some(interpreter)
+    }
+}
+
+/// An error during the execution of some code.
+#[derive(thiserror::Error, Debug)]
+pub enum CodeExecuteError {
+    #[error("code execution is not supported for {0:?}")]
+    UnsupportedLanguage(ProgrammingLanguage),
+
+    #[error("spawning '{0}' failed: {1}")]
+    SpawnProcess(&'static str, io::Error),
+}
+
+/// A handle for a process that's executing a piece of code.
+#[derive(Clone, Debug)]
+pub struct ExecutionHandle {
+    state: Arc<Mutex<ExecutionState>>,
+}
+
+impl ExecutionHandle {
+    /// Get a snapshot of the current state of the execution.
+    pub fn state(&self) -> ExecutionState {
+        self.state.lock().unwrap().clone()
+    }
+}
+
+/// The state of a process that's executing a piece of code.
+#[derive(Clone, Debug, Default)]
+pub struct ExecutionState {
+    /// The lines the process has written to stdout and stderr so far.
+    pub output: Vec<String>,
+
+    /// The status of the process.
+    pub status: ProcessStatus,
+}
+
+/// The status of a process.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ProcessStatus {
+    /// The process is still running.
+    #[default]
+    Running,
+
+    /// The process finished successfully.
+    Success,
+
+    /// The process failed, potentially with an exit code.
+    Failure(Option<i32>),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::markdown::elements::CodeAttributes;
+    use std::time::{Duration, Instant};
+
+    fn wait(handle: &ExecutionHandle) -> ExecutionState {
+        let start = Instant::now();
+        loop {
+            let state = handle.state();
+            if state.status != ProcessStatus::Running {
+                return state;
+            }
+            assert!(start.elapsed() < Duration::from_secs(10), "process didn't finish");
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+
+    fn shell_code(contents: &str) -> Code {
+        Code {
+            contents: contents.into(),
+            language: ProgrammingLanguage::Shell,
+            language_name: "sh".into(),
+            attributes: CodeAttributes::default(),
+        }
+    }
+
+    #[test]
+    fn shell_code_execution() {
+        let handle = CodeExecuter::execute(&shell_code("echo 'hello world'\necho bye")).expect("execution failed");
+        let state = wait(&handle);
+        assert_eq!(state.output, &["hello world", "bye"]);
+        assert_eq!(state.status, ProcessStatus::Success);
+    }
+
+    #[test]
+    fn failed_execution() {
+        let handle = CodeExecuter::execute(&shell_code("echo oops >&2\nexit 3")).expect("execution failed");
+        let state = wait(&handle);
+        assert_eq!(state.output, &["oops"]);
+        assert_eq!(state.status, ProcessStatus::Failure(Some(3)));
+    }
+
+    #[test]
+    fn unsupported_language() {
+        let code = Code {
+            contents: "".into(),
+            language: ProgrammingLanguage::Rust,
+            language_name: "rust".into(),
+            attributes: Default::default(),
+        };
+        assert!(CodeExecuter::execute(&code).is_err());
+    }
+}
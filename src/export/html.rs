@@ -0,0 +1,266 @@
+use super::{color_to_rgb, style_runs, ExportError, SlideRenderer, DEFAULT_BACKGROUND, DEFAULT_FOREGROUND};
+use crate::{
+    presentation::Presentation,
+    render::{
+        grid::{CellStyle, GridCell, PlacedImage, TerminalGrid, WIDE_CHARACTER_CONTINUATION},
+        properties::WindowSize,
+    },
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use crossterm::style::Color;
+use image::ImageOutputFormat;
+use std::{fmt::Write as _, io};
+
+const STYLE: &str = r#"
+html, body { margin: 0; height: 100%; background: #000; overflow: hidden; }
+body { display: flex; align-items: center; justify-content: center; }
+.slide {
+  --row-height: 1.2em;
+  position: relative;
+  flex: none;
+  font-family: ui-monospace, Menlo, Consolas, "DejaVu Sans Mono", monospace;
+  font-size: 16px;
+  line-height: var(--row-height);
+  transform-origin: center;
+}
+.slide[hidden] { display: none; }
+.row { white-space: pre; height: var(--row-height); overflow: hidden; }
+.row a { color: inherit; }
+.image { position: absolute; object-fit: contain; }
+"#;
+
+const SCRIPT: &str = r##"
+const slides = Array.from(document.querySelectorAll(".slide"));
+let current = 0;
+let pending = "";
+
+function show(index) {
+  current = Math.max(0, Math.min(index, slides.length - 1));
+  slides.forEach((slide, i) => slide.hidden = i !== current);
+  history.replaceState(null, "", "#" + (current + 1));
+  resize();
+}
+
+function resize() {
+  const slide = slides[current];
+  const scale = Math.min(window.innerWidth / slide.offsetWidth, window.innerHeight / slide.offsetHeight);
+  slide.style.transform = "scale(" + scale + ")";
+}
+
+document.addEventListener("keydown", (event) => {
+  const key = event.key;
+  const previous = pending;
+  pending = "";
+  if (["h", "k", "ArrowLeft", "ArrowUp", "PageUp"].includes(key)) {
+    show(current - 1);
+  } else if (["l", "j", "ArrowRight", "ArrowDown", "PageDown", " "].includes(key)) {
+    show(current + 1);
+  } else if (key === "g" && previous === "g") {
+    show(0);
+  } else if (key === "G") {
+    show(previous.match(/^[0-9]+$/) ? parseInt(previous) - 1 : slides.length - 1);
+  } else if (key === "g" || key.match(/^[0-9]$/)) {
+    pending = key === "g" ? key : previous.replace(/[^0-9]/g, "") + key;
+  } else {
+    return;
+  }
+  event.preventDefault();
+});
+window.addEventListener("resize", resize);
+show(parseInt(location.hash.substring(1) || "1") - 1);
+"##;
+
+/// Exports presentations into a single, self contained, HTML file.
+///
+/// Every slide is rendered the same way it would be in a terminal of a fixed size and then turned
+/// into HTML. Images are embedded in the file and the slides can be navigated using the same keys
+/// used when running the presentation.
+pub struct HtmlExporter {
+    renderer: SlideRenderer,
+}
+
+impl HtmlExporter {
+    /// Construct a new exporter that renders slides using the given window size.
+    pub fn new(dimensions: WindowSize) -> Self {
+        Self { renderer: SlideRenderer::new(dimensions) }
+    }
+
+    /// Export a presentation into the given writer.
+    pub fn export<W: io::Write>(
+        &self,
+        presentation: &Presentation,
+        title: &str,
+        mut output: W,
+    ) -> Result<(), ExportError> {
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        let _ = writeln!(html, "<title>{}</title>", escape(title));
+        let _ = writeln!(html, "<style>{STYLE}</style>\n</head>\n<body>");
+        for slide in presentation.iter_slides() {
+            let slide = self.renderer.render(slide)?;
+            Self::write_slide(&mut html, slide)?;
+        }
+        let _ = writeln!(html, "<script>{SCRIPT}</script>\n</body>\n</html>");
+        output.write_all(html.as_bytes())?;
+        Ok(())
+    }
+
+    fn write_slide(html: &mut String, grid: TerminalGrid) -> Result<(), ExportError> {
+        let _ = writeln!(
+            html,
+            r#"<section class="slide" hidden style="width: {}ch; height: calc({} * var(--row-height)); {}">"#,
+            grid.columns(),
+            grid.row_count(),
+            colors_css(None, None),
+        );
+        for row in grid.rows() {
+            html.push_str(r#"<div class="row">"#);
+            for (start, length, cell) in style_runs(row, |a, b| a.style == b.style) {
+                let text: String = row[start..start + length]
+                    .iter()
+                    .map(|cell| cell.character)
+                    .filter(|c| *c != WIDE_CHARACTER_CONTINUATION)
+                    .collect();
+                write_span(html, &text, cell);
+            }
+            html.push_str("</div>\n");
+        }
+        for image in grid.images() {
+            write_image(html, image)?;
+        }
+        html.push_str("</section>\n");
+        Ok(())
+    }
+}
+
+fn write_span(html: &mut String, text: &str, cell: &GridCell) {
+    let CellStyle { colors, bold, italics, underlined, strikethrough, url } = &cell.style;
+    let mut style = String::new();
+    if colors.foreground.is_some() || colors.background.is_some() {
+        style.push_str(&colors_css(colors.foreground, colors.background));
+    }
+    if *bold {
+        style.push_str(" font-weight: bold;");
+    }
+    if *italics {
+        style.push_str(" font-style: italic;");
+    }
+    let decorations: Vec<_> =
+        [(*underlined, "underline"), (*strikethrough, "line-through")].iter().filter(|d| d.0).map(|d| d.1).collect();
+    if !decorations.is_empty() {
+        let _ = write!(style, " text-decoration: {};", decorations.join(" "));
+    }
+    let mut text = escape(text);
+    if !style.is_empty() {
+        text = format!(r#"<span style="{}">{text}</span>"#, style.trim_start());
+    }
+    match url {
+        Some(url) => {
+            let _ = write!(html, r#"<a href="{}">{text}</a>"#, escape(url));
+        }
+        None => html.push_str(&text),
+    };
+}
+
+fn write_image(html: &mut String, placed: &PlacedImage) -> Result<(), ExportError> {
+    let mut contents = io::Cursor::new(Vec::new());
+    placed.image.contents().write_to(&mut contents, ImageOutputFormat::Png)?;
+    let contents = STANDARD.encode(contents.into_inner());
+    let _ = writeln!(
+        html,
+        r#"<img class="image" src="data:image/png;base64,{contents}" style="left: {}ch; top: calc({} * var(--row-height)); width: {}ch; height: calc({} * var(--row-height));">"#,
+        placed.column, placed.row, placed.columns, placed.rows,
+    );
+    Ok(())
+}
+
+fn colors_css(foreground: Option<Color>, background: Option<Color>) -> String {
+    let foreground = foreground.and_then(color_to_rgb).unwrap_or(DEFAULT_FOREGROUND);
+    let background = background.and_then(color_to_rgb).unwrap_or(DEFAULT_BACKGROUND);
+    format!("color: {}; background: {};", css_color(foreground), css_color(background))
+}
+
+fn css_color((r, g, b): (u8, u8, u8)) -> String {
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape(text: &str) -> String {
+    let mut output = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => output.push_str("&amp;"),
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '"' => output.push_str("&quot;"),
+            '\'' => output.push_str("&#39;"),
+            _ => output.push(c),
+        }
+    }
+    output
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        markdown::{
+            elements::StyledText,
+            text::{WeightedLine, WeightedText},
+        },
+        presentation::{RenderOperation, Slide},
+        render::media::Image,
+        style::TextStyle,
+        theme::{Alignment, Colors},
+    };
+    use image::DynamicImage;
+
+    fn export(slides: Vec<Slide>) -> String {
+        let presentation = Presentation::new(slides);
+        let dimensions = WindowSize { rows: 10, columns: 40, width: 320, height: 160 };
+        let mut output = Vec::new();
+        HtmlExporter::new(dimensions).export(&presentation, "<title>", &mut output).expect("export failed");
+        String::from_utf8(output).expect("invalid utf8")
+    }
+
+    fn text_slide(text: StyledText) -> Slide {
+        let line = WeightedLine::from(vec![WeightedText::from(text)]);
+        let render_operations = vec![
+            RenderOperation::ClearScreen,
+            RenderOperation::RenderTextLine { line, alignment: Alignment::Left { margin: 0 } },
+        ];
+        Slide { render_operations, notes: Vec::new(), after_pause: false }
+    }
+
+    #[test]
+    fn slides() {
+        let html = export(vec![text_slide(StyledText::from("a")), text_slide(StyledText::from("b"))]);
+        assert_eq!(html.matches("<section").count(), 2);
+        assert!(html.contains("<title>&lt;title&gt;</title>"));
+        assert!(html.contains(&format!(r#"<div class="row">a{}</div>"#, " ".repeat(39))));
+    }
+
+    #[test]
+    fn styled_text() {
+        let colors = Colors { foreground: Some(Color::Red), background: None };
+        let style = TextStyle::default().bold().strikethrough().colors(colors);
+        let html = export(vec![text_slide(StyledText::new("<hi>", style))]);
+        let expected = r#"<span style="color: #ff0000; background: #ffffff; font-weight: bold; text-decoration: line-through;">&lt;hi&gt;</span>"#;
+        assert!(html.contains(expected), "{html}");
+    }
+
+    #[test]
+    fn links() {
+        let style = TextStyle::default().link("https://example.com/?a=1&b=2");
+        let html = export(vec![text_slide(StyledText::new("site", style))]);
+        let expected = r#"<a href="https://example.com/?a=1&amp;b=2"><span style="font-style: italic; text-decoration: underline;">site</span></a>"#;
+        assert!(html.contains(expected), "{html}");
+    }
+
+    #[test]
+    fn embedded_images() {
+        let image = Image::from(DynamicImage::new_rgb8(16, 16));
+        let render_operations = vec![RenderOperation::ClearScreen, RenderOperation::RenderImage(image)];
+        let html = export(vec![Slide { render_operations, notes: Vec::new(), after_pause: false }]);
+        assert!(html.contains(r#"src="data:image/png;base64,iVBORw0KGgo"#));
+    }
+}
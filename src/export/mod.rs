@@ -0,0 +1,203 @@
+use crate::{
+    presentation::{Presentation, Slide},
+    render::{
+        draw::{render_slide, RenderError, SlideOverflow},
+        grid::{GridCell, TerminalGrid},
+        properties::WindowSize,
+    },
+};
+use crossterm::style::Color;
+use image::ImageError;
+use std::io;
+
+pub mod html;
+pub mod pdf;
+
+/// The window size presentations are rendered into when exporting them.
+///
+/// This uses 8x16 pixel cells, which is what most terminal fonts look like.
+pub const EXPORT_WINDOW_SIZE: WindowSize = WindowSize { rows: 34, columns: 120, width: 960, height: 544 };
+
+/// The foreground color used for cells that don't have one.
+pub const DEFAULT_FOREGROUND: (u8, u8, u8) = (0, 0, 0);
+
+/// The background color used for cells that don't have one.
+pub const DEFAULT_BACKGROUND: (u8, u8, u8) = (255, 255, 255);
+
+/// Renders slides into a [TerminalGrid] rather than into a terminal.
+pub struct SlideRenderer {
+    dimensions: WindowSize,
+}
+
+impl SlideRenderer {
+    /// Construct a new renderer that uses a window of the given size.
+    pub fn new(dimensions: WindowSize) -> Self {
+        Self { dimensions }
+    }
+
+    /// Render a slide.
+    pub fn render(&self, slide: &Slide) -> Result<TerminalGrid, RenderError> {
+        self.render_with_overflow(slide).map(|(grid, _)| grid)
+    }
+
+    /// Render a slide, also returning how much it overflows the window.
+    pub fn render_with_overflow(&self, slide: &Slide) -> Result<(TerminalGrid, SlideOverflow), RenderError> {
+        let mut grid = TerminalGrid::new(self.dimensions.clone());
+        let overflow = render_slide(&mut grid, slide, 0)?;
+        Ok((grid, overflow))
+    }
+
+    /// Render the slide at the given zero based index in a presentation.
+    pub fn render_slide_at(&self, presentation: &Presentation, index: usize) -> Result<TerminalGrid, RenderError> {
+        let slide = presentation.iter_slides().nth(index).ok_or(RenderError::NoSuchSlide(index))?;
+        self.render(slide)
+    }
+}
+
+/// Split a row into `(start, length, first cell)` runs of consecutive cells that are equivalent.
+pub(crate) fn style_runs<F>(row: &[GridCell], equivalent: F) -> Vec<(usize, usize, &GridCell)>
+where
+    F: Fn(&GridCell, &GridCell) -> bool,
+{
+    let mut runs: Vec<(usize, usize, &GridCell)> = Vec::new();
+    for (index, cell) in row.iter().enumerate() {
+        match runs.last_mut() {
+            Some((_, length, first)) if equivalent(first, cell) => *length += 1,
+            _ => runs.push((index, 1, cell)),
+        };
+    }
+    runs
+}
+
+/// Convert a color into its RGB components.
+///
+/// Returns `None` if this is [Color::Reset], meaning the default color should be used.
+pub fn color_to_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let rgb = match color {
+        Color::Reset => return None,
+        Color::Black => (0, 0, 0),
+        Color::DarkRed => (128, 0, 0),
+        Color::DarkGreen => (0, 128, 0),
+        Color::DarkYellow => (128, 128, 0),
+        Color::DarkBlue => (0, 0, 128),
+        Color::DarkMagenta => (128, 0, 128),
+        Color::DarkCyan => (0, 128, 128),
+        Color::Grey => (192, 192, 192),
+        Color::DarkGrey => (128, 128, 128),
+        Color::Red => (255, 0, 0),
+        Color::Green => (0, 255, 0),
+        Color::Yellow => (255, 255, 0),
+        Color::Blue => (0, 0, 255),
+        Color::Magenta => (255, 0, 255),
+        Color::Cyan => (0, 255, 255),
+        Color::White => (255, 255, 255),
+        Color::Rgb { r, g, b } => (r, g, b),
+        Color::AnsiValue(value) => ansi_value_to_rgb(value),
+    };
+    Some(rgb)
+}
+
+fn ansi_value_to_rgb(value: u8) -> (u8, u8, u8) {
+    const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+    match value {
+        0..=15 => {
+            let colors = [
+                Color::Black,
+                Color::DarkRed,
+                Color::DarkGreen,
+                Color::DarkYellow,
+                Color::DarkBlue,
+                Color::DarkMagenta,
+                Color::DarkCyan,
+                Color::Grey,
+                Color::DarkGrey,
+                Color::Red,
+                Color::Green,
+                Color::Yellow,
+                Color::Blue,
+                Color::Magenta,
+                Color::Cyan,
+                Color::White,
+            ];
+            color_to_rgb(colors[value as usize]).unwrap_or_default()
+        }
+        16..=231 => {
+            let value = value - 16;
+            let (r, g, b) = (value / 36, (value / 6) % 6, value % 6);
+            (CUBE_LEVELS[r as usize], CUBE_LEVELS[g as usize], CUBE_LEVELS[b as usize])
+        }
+        232..=255 => {
+            let level = 8 + (value - 232) * 10;
+            (level, level, level)
+        }
+    }
+}
+
+/// An error during an export.
+#[derive(thiserror::Error, Debug)]
+pub enum ExportError {
+    #[error(transparent)]
+    Render(#[from] RenderError),
+
+    #[error("io: {0}")]
+    Io(#[from] io::Error),
+
+    #[error("generating pdf: {0}")]
+    Pdf(#[from] printpdf::Error),
+
+    #[error("encoding image: {0}")]
+    Image(#[from] ImageError),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        markdown::{
+            elements::StyledText,
+            text::{WeightedLine, WeightedText},
+        },
+        presentation::RenderOperation,
+        render::media::Image,
+        theme::{Alignment, Colors},
+    };
+    use image::DynamicImage;
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::reset(Color::Reset, None)]
+    #[case::named(Color::DarkCyan, Some((0, 128, 128)))]
+    #[case::rgb(Color::Rgb { r: 1, g: 2, b: 3 }, Some((1, 2, 3)))]
+    #[case::ansi_named(Color::AnsiValue(9), Some((255, 0, 0)))]
+    #[case::ansi_cube(Color::AnsiValue(110), Some((135, 175, 215)))]
+    #[case::ansi_grayscale(Color::AnsiValue(240), Some((88, 88, 88)))]
+    fn color_conversion(#[case] color: Color, #[case] expected: Option<(u8, u8, u8)>) {
+        assert_eq!(color_to_rgb(color), expected);
+    }
+
+    #[test]
+    fn render_slide() {
+        let text = WeightedLine::from(vec![WeightedText::from(StyledText::from("hello"))]);
+        let image = Image::from(DynamicImage::new_rgb8(160, 64));
+        let colors = Colors { foreground: Some(Color::Red), background: Some(Color::Black) };
+        let render_operations = vec![
+            RenderOperation::SetColors(colors.clone()),
+            RenderOperation::ClearScreen,
+            RenderOperation::RenderTextLine { line: text, alignment: Alignment::Left { margin: 2 } },
+            RenderOperation::RenderLineBreak,
+            RenderOperation::RenderImage(image),
+        ];
+        let slide = Slide { render_operations, notes: Vec::new(), after_pause: false };
+        let dimensions = WindowSize { rows: 10, columns: 40, width: 320, height: 160 };
+        let grid = SlideRenderer::new(dimensions).render(&slide).expect("render failed");
+
+        assert_eq!(grid.row_text(0), format!("  hello{}", " ".repeat(33)));
+        assert_eq!(grid.cell(2, 0).unwrap().style.colors, colors);
+        // Cells that weren't written to keep the background color from clearing the screen.
+        assert_eq!(grid.cell(30, 9).unwrap().style.colors.background, Some(Color::Black));
+
+        let image = &grid.images()[0];
+        assert_eq!((image.column, image.row, image.columns, image.rows), (10, 1, 20, 4));
+        assert_eq!(grid.cursor_position(), (0, 5));
+    }
+}
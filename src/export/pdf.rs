@@ -0,0 +1,409 @@
+use super::{color_to_rgb, style_runs, ExportError, SlideRenderer, DEFAULT_BACKGROUND, DEFAULT_FOREGROUND};
+use crate::{
+    presentation::Presentation,
+    render::{
+        grid::{CellStyle, GridCell, TerminalGrid, WIDE_CHARACTER_CONTINUATION},
+        properties::WindowSize,
+    },
+};
+use crossterm::style::Color;
+use printpdf::{
+    path::PaintMode, Actions, BorderArray, BuiltinFont, Color as PdfColor, ImageTransform, IndirectFontRef,
+    LinkAnnotation, Mm, PdfDocument, PdfDocumentReference, PdfLayerReference, Pt, Rect, Rgb,
+};
+use std::io::{self, BufWriter};
+
+const FONT_SIZE: f32 = 10.0;
+// Courier glyphs are 0.6 ems wide.
+const CELL_WIDTH: f32 = FONT_SIZE * 0.6;
+const CELL_HEIGHT: f32 = FONT_SIZE * 1.2;
+const BASELINE_OFFSET: f32 = CELL_HEIGHT * 0.25;
+const LINE_THICKNESS: f32 = 0.6;
+
+/// Exports presentations into PDF files.
+///
+/// Every slide is rendered the same way it would be in a terminal of a fixed size and then turned
+/// into a PDF page.
+pub struct PdfExporter {
+    renderer: SlideRenderer,
+    dimensions: WindowSize,
+}
+
+impl PdfExporter {
+    /// Construct a new exporter that renders slides using the given window size.
+    pub fn new(dimensions: WindowSize) -> Self {
+        Self { renderer: SlideRenderer::new(dimensions.clone()), dimensions }
+    }
+
+    /// Export a presentation into the given writer.
+    pub fn export<W: io::Write>(&self, presentation: &Presentation, title: &str, output: W) -> Result<(), ExportError> {
+        let width = Mm::from(Pt(self.dimensions.columns as f32 * CELL_WIDTH));
+        let height = Mm::from(Pt(self.dimensions.rows as f32 * CELL_HEIGHT));
+        let (document, page, layer) = PdfDocument::new(title, width, height, "Slide 1");
+        let fonts = PdfFonts::new(&document)?;
+        let mut current_layer = document.get_page(page).get_layer(layer);
+        for (index, slide) in presentation.iter_slides().enumerate() {
+            if index > 0 {
+                let (page, layer) = document.add_page(width, height, format!("Slide {}", index + 1));
+                current_layer = document.get_page(page).get_layer(layer);
+            }
+            let slide = self.renderer.render(slide)?;
+            let page = PageDrawer { layer: &current_layer, fonts: &fonts, rows: self.dimensions.rows };
+            page.draw(slide);
+        }
+        document.save(&mut BufWriter::new(output))?;
+        Ok(())
+    }
+}
+
+struct PdfFonts {
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    italics: IndirectFontRef,
+    bold_italics: IndirectFontRef,
+}
+
+impl PdfFonts {
+    fn new(document: &PdfDocumentReference) -> Result<Self, printpdf::Error> {
+        Ok(Self {
+            regular: document.add_builtin_font(BuiltinFont::Courier)?,
+            bold: document.add_builtin_font(BuiltinFont::CourierBold)?,
+            italics: document.add_builtin_font(BuiltinFont::CourierOblique)?,
+            bold_italics: document.add_builtin_font(BuiltinFont::CourierBoldOblique)?,
+        })
+    }
+
+    fn select(&self, style: &CellStyle) -> &IndirectFontRef {
+        match (style.bold, style.italics) {
+            (false, false) => &self.regular,
+            (true, false) => &self.bold,
+            (false, true) => &self.italics,
+            (true, true) => &self.bold_italics,
+        }
+    }
+}
+
+struct PageDrawer<'a> {
+    layer: &'a PdfLayerReference,
+    fonts: &'a PdfFonts,
+    rows: u16,
+}
+
+impl<'a> PageDrawer<'a> {
+    fn draw(&self, grid: TerminalGrid) {
+        let columns = grid.columns() as f32;
+        self.fill(DEFAULT_BACKGROUND, 0.0, 0.0, columns * CELL_WIDTH, self.rows as f32 * CELL_HEIGHT);
+        for (row_index, row) in grid.rows().iter().enumerate() {
+            let bottom = (self.rows as f32 - row_index as f32 - 1.0) * CELL_HEIGHT;
+            self.draw_backgrounds(row, bottom);
+            self.draw_text(row, bottom);
+            self.draw_links(row, bottom);
+        }
+        for placed in grid.images() {
+            let image = placed.image.contents();
+            let width = placed.columns as f32 * CELL_WIDTH;
+            let height = placed.rows as f32 * CELL_HEIGHT;
+            // Keep the aspect ratio and center the image vertically within its rows.
+            let scale = width / image.width().max(1) as f32;
+            let image_height = image.height() as f32 * scale;
+            let bottom = (self.rows as f32 - (placed.row + placed.rows) as f32) * CELL_HEIGHT;
+            let transform = ImageTransform {
+                translate_x: Some(Mm::from(Pt(placed.column as f32 * CELL_WIDTH))),
+                translate_y: Some(Mm::from(Pt(bottom + (height - image_height) / 2.0))),
+                scale_x: Some(scale),
+                scale_y: Some(scale),
+                // At 72 DPI one pixel is one point.
+                dpi: Some(72.0),
+                ..Default::default()
+            };
+            printpdf::Image::from_dynamic_image(image).add_to_layer(self.layer.clone(), transform);
+        }
+    }
+
+    fn draw_backgrounds(&self, row: &[GridCell], bottom: f32) {
+        for (start, length, cell) in style_runs(row, |a, b| a.style.colors.background == b.style.colors.background) {
+            if let Some(color) = cell.style.colors.background.and_then(color_to_rgb) {
+                let left = start as f32 * CELL_WIDTH;
+                self.fill(color, left, bottom, left + length as f32 * CELL_WIDTH, bottom + CELL_HEIGHT);
+            }
+        }
+    }
+
+    fn draw_text(&self, row: &[GridCell], bottom: f32) {
+        for (start, length, cell) in style_runs(row, |a, b| a.style == b.style) {
+            let style = &cell.style;
+            let color = Self::foreground(style.colors.foreground);
+            let left = start as f32 * CELL_WIDTH;
+            let mut text = String::new();
+            for (offset, cell) in row[start..start + length].iter().enumerate() {
+                let cell_left = left + offset as f32 * CELL_WIDTH;
+                match Glyph::from(cell.character) {
+                    Glyph::Text(character) => text.push(character),
+                    Glyph::Shapes(shapes) => {
+                        for (x0, y0, x1, y1) in shapes {
+                            let (x0, x1) = (cell_left + x0 * CELL_WIDTH, cell_left + x1 * CELL_WIDTH);
+                            let (y0, y1) = (bottom + y0 * CELL_HEIGHT, bottom + y1 * CELL_HEIGHT);
+                            self.fill(color, x0, y0, x1, y1);
+                        }
+                        text.push(' ');
+                    }
+                    Glyph::Shade(density) => {
+                        let background = style.colors.background.and_then(color_to_rgb).unwrap_or(DEFAULT_BACKGROUND);
+                        let color = Self::blend(color, background, density);
+                        self.fill(color, cell_left, bottom, cell_left + CELL_WIDTH, bottom + CELL_HEIGHT);
+                        text.push(' ');
+                    }
+                }
+            }
+            let right = left + length as f32 * CELL_WIDTH;
+            if style.underlined {
+                let y = bottom + BASELINE_OFFSET - LINE_THICKNESS * 2.0;
+                self.fill(color, left, y, right, y + LINE_THICKNESS);
+            }
+            if style.strikethrough {
+                let y = bottom + CELL_HEIGHT / 2.0;
+                self.fill(color, left, y, right, y + LINE_THICKNESS);
+            }
+            if text.trim().is_empty() {
+                continue;
+            }
+            self.layer.set_fill_color(Self::pdf_color(color));
+            let font = self.fonts.select(style);
+            let (x, y) = (Mm::from(Pt(left)), Mm::from(Pt(bottom + BASELINE_OFFSET)));
+            self.layer.use_text(text, FONT_SIZE, x, y, font);
+        }
+    }
+
+    fn draw_links(&self, row: &[GridCell], bottom: f32) {
+        for (start, length, cell) in style_runs(row, |a, b| a.style.url == b.style.url) {
+            let Some(url) = &cell.style.url else {
+                continue;
+            };
+            let (left, right) = (start as f32 * CELL_WIDTH, (start + length) as f32 * CELL_WIDTH);
+            let rect = Rect::new(
+                Mm::from(Pt(left)),
+                Mm::from(Pt(bottom)),
+                Mm::from(Pt(right)),
+                Mm::from(Pt(bottom + CELL_HEIGHT)),
+            );
+            // Links are already underlined so don't draw a border around them.
+            let border = BorderArray::Solid([0.0, 0.0, 0.0]);
+            let annotation = LinkAnnotation::new(rect, Some(border), None, Actions::uri(url.clone()), None);
+            self.layer.add_link_annotation(annotation);
+        }
+    }
+
+    fn fill(&self, color: (u8, u8, u8), x0: f32, y0: f32, x1: f32, y1: f32) {
+        self.layer.set_fill_color(Self::pdf_color(color));
+        let rect = Rect::new(Mm::from(Pt(x0)), Mm::from(Pt(y0)), Mm::from(Pt(x1)), Mm::from(Pt(y1)))
+            .with_mode(PaintMode::Fill);
+        self.layer.add_rect(rect);
+    }
+
+    fn foreground(color: Option<Color>) -> (u8, u8, u8) {
+        color.and_then(color_to_rgb).unwrap_or(DEFAULT_FOREGROUND)
+    }
+
+    fn blend(foreground: (u8, u8, u8), background: (u8, u8, u8), density: f32) -> (u8, u8, u8) {
+        let mix = |foreground: u8, background: u8| {
+            (foreground as f32 * density + background as f32 * (1.0 - density)).round() as u8
+        };
+        (mix(foreground.0, background.0), mix(foreground.1, background.1), mix(foreground.2, background.2))
+    }
+
+    fn pdf_color((r, g, b): (u8, u8, u8)) -> PdfColor {
+        PdfColor::Rgb(Rgb::new(r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, None))
+    }
+}
+
+// A rectangle within a cell, as `(x0, y0, x1, y1)` fractions of the cell's width and height
+// starting from its bottom left corner.
+type Shape = (f32, f32, f32, f32);
+
+/// How a character in the grid is drawn in a PDF page.
+#[derive(Debug, PartialEq)]
+enum Glyph {
+    /// Draw it as text.
+    Text(char),
+
+    /// Draw it as a set of filled rectangles.
+    ///
+    /// This is used for block and box drawing characters, which are both very common in slides
+    /// and not supported by the builtin PDF fonts.
+    Shapes(Vec<Shape>),
+
+    /// Fill the entire cell using a mix of the foreground and background colors.
+    ///
+    /// The value is the proportion of the foreground color to be used.
+    Shade(f32),
+}
+
+impl From<char> for Glyph {
+    fn from(character: char) -> Self {
+        // Box drawing lines are a bit thicker horizontally given cells are twice as tall as wide.
+        const HORIZONTAL: f32 = 0.05;
+        const VERTICAL: f32 = 0.1;
+        let (left, right, up, down) = match character {
+            '█' => return Self::Shapes(vec![(0.0, 0.0, 1.0, 1.0)]),
+            '▀' => return Self::Shapes(vec![(0.0, 0.5, 1.0, 1.0)]),
+            '▐' => return Self::Shapes(vec![(0.5, 0.0, 1.0, 1.0)]),
+            '░' => return Self::Shade(0.25),
+            '▒' => return Self::Shade(0.5),
+            '▓' => return Self::Shade(0.75),
+            '▪' => return Self::Shapes(vec![(0.25, 0.375, 0.75, 0.625)]),
+            // Lower eighth blocks.
+            '▁'..='▇' => {
+                let height = (character as u32 - '▁' as u32 + 1) as f32 / 8.0;
+                return Self::Shapes(vec![(0.0, 0.0, 1.0, height)]);
+            }
+            // Left eighth blocks, from 7/8 down to 1/8.
+            '▉'..='▏' => {
+                let width = (8 - (character as u32 - '▉' as u32 + 1)) as f32 / 8.0;
+                return Self::Shapes(vec![(0.0, 0.0, width, 1.0)]);
+            }
+            '─' | '━' => (true, true, false, false),
+            '│' | '┃' => (false, false, true, true),
+            '┌' | '╭' | '┏' => (false, true, false, true),
+            '┐' | '╮' | '┓' => (true, false, false, true),
+            '└' | '╰' | '┗' => (false, true, true, false),
+            '┘' | '╯' | '┛' => (true, false, true, false),
+            '├' => (false, true, true, true),
+            '┤' => (true, false, true, true),
+            '┬' => (true, true, false, true),
+            '┴' => (true, true, true, false),
+            '┼' => (true, true, true, true),
+            WIDE_CHARACTER_CONTINUATION => return Self::Text(' '),
+            '◦' => return Self::Text('o'),
+            character if is_win_ansi(character) => return Self::Text(character),
+            _ => return Self::Text('?'),
+        };
+        let mut shapes = Vec::new();
+        if left {
+            shapes.push((0.0, 0.5 - HORIZONTAL, 0.5 + VERTICAL, 0.5 + HORIZONTAL));
+        }
+        if right {
+            shapes.push((0.5 - VERTICAL, 0.5 - HORIZONTAL, 1.0, 0.5 + HORIZONTAL));
+        }
+        if up {
+            shapes.push((0.5 - VERTICAL, 0.5 - HORIZONTAL, 0.5 + VERTICAL, 1.0));
+        }
+        if down {
+            shapes.push((0.5 - VERTICAL, 0.0, 0.5 + VERTICAL, 0.5 + HORIZONTAL));
+        }
+        Self::Shapes(shapes)
+    }
+}
+
+// Whether a character can be encoded using the Windows-1252 encoding the builtin fonts use.
+fn is_win_ansi(character: char) -> bool {
+    matches!(
+        character,
+        ' '..='~'
+            | '\u{a0}'..='\u{ff}'
+            | '€'
+            | '‚'
+            | 'ƒ'
+            | '„'
+            | '…'
+            | '†'
+            | '‡'
+            | 'ˆ'
+            | '‰'
+            | 'Š'
+            | '‹'
+            | 'Œ'
+            | 'Ž'
+            | '‘'
+            | '’'
+            | '“'
+            | '”'
+            | '•'
+            | '–'
+            | '—'
+            | '˜'
+            | '™'
+            | 'š'
+            | '›'
+            | 'œ'
+            | 'ž'
+            | 'Ÿ'
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        markdown::{
+            elements::StyledText,
+            text::{WeightedLine, WeightedText},
+        },
+        presentation::{RenderOperation, Slide},
+        style::TextStyle,
+        theme::Alignment,
+    };
+    use rstest::rstest;
+
+    #[rstest]
+    #[case::ascii('a', Glyph::Text('a'))]
+    #[case::latin('ñ', Glyph::Text('ñ'))]
+    #[case::em_dash('—', Glyph::Text('—'))]
+    #[case::bullet('•', Glyph::Text('•'))]
+    #[case::unsupported('日', Glyph::Text('?'))]
+    #[case::full_block('█', Glyph::Shapes(vec![(0.0, 0.0, 1.0, 1.0)]))]
+    #[case::lower_half('▄', Glyph::Shapes(vec![(0.0, 0.0, 1.0, 0.5)]))]
+    #[case::shade('▒', Glyph::Shade(0.5))]
+    #[case::left_three_eighths('▍', Glyph::Shapes(vec![(0.0, 0.0, 0.375, 1.0)]))]
+    fn glyphs(#[case] character: char, #[case] expected: Glyph) {
+        assert_eq!(Glyph::from(character), expected);
+    }
+
+    #[test]
+    fn box_drawing() {
+        let Glyph::Shapes(shapes) = Glyph::from('┼') else { panic!("not shapes") };
+        assert_eq!(shapes.len(), 4);
+    }
+
+    #[test]
+    fn export() {
+        let slides = (0..2)
+            .map(|index| {
+                let text = WeightedLine::from(vec![WeightedText::from(StyledText::from(format!("slide {index}")))]);
+                let render_operations = vec![
+                    RenderOperation::ClearScreen,
+                    RenderOperation::RenderTextLine { line: text, alignment: Alignment::Left { margin: 0 } },
+                ];
+                Slide { render_operations, notes: Vec::new(), after_pause: false }
+            })
+            .collect();
+        let presentation = Presentation::new(slides);
+        let dimensions = WindowSize { rows: 10, columns: 40, width: 320, height: 160 };
+        let mut output = Vec::new();
+        PdfExporter::new(dimensions).export(&presentation, "test", &mut output).expect("export failed");
+
+        let document = printpdf::lopdf::Document::load_mem(&output).expect("invalid pdf");
+        assert_eq!(document.get_pages().len(), 2);
+    }
+
+    #[test]
+    fn links() {
+        let style = TextStyle::default().link("https://example.com");
+        let text = WeightedLine::from(vec![WeightedText::from(StyledText::new("site", style))]);
+        let render_operations = vec![
+            RenderOperation::ClearScreen,
+            RenderOperation::RenderTextLine { line: text, alignment: Alignment::Left { margin: 0 } },
+        ];
+        let presentation = Presentation::new(vec![Slide { render_operations, notes: Vec::new(), after_pause: false }]);
+        let dimensions = WindowSize { rows: 10, columns: 40, width: 320, height: 160 };
+        let mut output = Vec::new();
+        PdfExporter::new(dimensions).export(&presentation, "test", &mut output).expect("export failed");
+
+        let document = printpdf::lopdf::Document::load_mem(&output).expect("invalid pdf");
+        let (_, page) = document.get_pages().into_iter().next().expect("no pages");
+        let annotations = document.get_page_annotations(page);
+        assert_eq!(annotations.len(), 1);
+        let action = annotations[0].get(b"A").and_then(|action| action.as_dict()).expect("no action");
+        let uri = action.get(b"URI").and_then(|uri| uri.as_str()).expect("no uri");
+        assert_eq!(uri, b"https://example.com");
+    }
+}
@@ -1,25 +1,60 @@
 use std::{fs, io, path::PathBuf, time::SystemTime};
 
-/// Watchers the presentation's file.
+/// Watchers the presentation's files.
 ///
 /// This uses polling rather than something fancier like `inotify`. The latter turned out to make
-/// code too complex for little added gain. This instead keeps the last modified time for the given
-/// path and uses that to determine if it's changed.
+/// code too complex for little added gain. This instead keeps the last modified time for every
+/// watched path and uses that to determine if any of them changed.
 pub struct PresentationFileWatcher {
-    path: PathBuf,
-    last_modification: SystemTime,
+    files: Vec<WatchedFile>,
 }
 
 impl PresentationFileWatcher {
     /// Create a watcher over the given file path.
     pub fn new<P: Into<PathBuf>>(path: P) -> Self {
-        let path = path.into();
+        Self { files: vec![WatchedFile::new(path.into())] }
+    }
+
+    /// Watch the given paths along with the one this watcher was created with.
+    ///
+    /// This replaces any paths passed in previous calls.
+    pub fn watch<I: IntoIterator<Item = PathBuf>>(&mut self, paths: I) {
+        self.files.truncate(1);
+        for path in paths {
+            if !self.files.iter().any(|file| file.path == path) {
+                self.files.push(WatchedFile::new(path));
+            }
+        }
+    }
+
+    /// Checker whether any of the watched files has modifications.
+    pub fn has_modifications(&mut self) -> io::Result<bool> {
+        let mut modified = false;
+        for (index, file) in self.files.iter_mut().enumerate() {
+            match file.has_modifications() {
+                Ok(true) => modified = true,
+                Ok(false) => (),
+                // Included files may be gone by the time the presentation stops referencing them.
+                Err(e) if index > 0 && e.kind() == io::ErrorKind::NotFound => (),
+                Err(e) => return Err(e),
+            };
+        }
+        Ok(modified)
+    }
+}
+
+struct WatchedFile {
+    path: PathBuf,
+    last_modification: SystemTime,
+}
+
+impl WatchedFile {
+    fn new(path: PathBuf) -> Self {
         let last_modification = fs::metadata(&path).and_then(|m| m.modified()).unwrap_or(SystemTime::UNIX_EPOCH);
         Self { path, last_modification }
     }
 
-    /// Checker whether this file has modifications.
-    pub fn has_modifications(&mut self) -> io::Result<bool> {
+    fn has_modifications(&mut self) -> io::Result<bool> {
         let metadata = fs::metadata(&self.path)?;
         let modified_time = metadata.modified()?;
         if modified_time > self.last_modification {
@@ -20,23 +20,37 @@ impl CommandSource {
         Self { watcher, user_input: UserInput::default() }
     }
 
+    /// Also watch the given paths, replacing any passed in previous calls.
+    ///
+    /// This is meant for files the presentation includes so changes to them reload it as well.
+    pub fn watch<I: IntoIterator<Item = PathBuf>>(&mut self, paths: I) {
+        self.watcher.watch(paths);
+    }
+
     /// Block until the next command arrives.
     pub fn next_command(&mut self) -> io::Result<Command> {
         loop {
-            match self.user_input.poll_next_command(Duration::from_millis(250)) {
-                Ok(Some(command)) => {
-                    return Ok(Command::User(command));
-                }
-                Ok(None) => (),
-                Err(e) => {
-                    return Ok(Command::Abort { error: e.to_string() });
-                }
-            };
-            if self.watcher.has_modifications()? {
-                return Ok(Command::ReloadPresentation);
+            if let Some(command) = self.try_next_command()? {
+                return Ok(command);
             }
         }
     }
+
+    /// Wait for a short amount of time for the next command to arrive.
+    ///
+    /// Returns `None` if no command arrived in the meantime.
+    pub fn try_next_command(&mut self) -> io::Result<Option<Command>> {
+        match self.user_input.poll_next_command(Duration::from_millis(250)) {
+            Ok(Some(command)) => {
+                return Ok(Some(Command::User(command)));
+            }
+            Ok(None) => (),
+            Err(e) => {
+                return Ok(Some(Command::Abort { error: e.to_string() }));
+            }
+        };
+        if self.watcher.has_modifications()? { Ok(Some(Command::ReloadPresentation)) } else { Ok(None) }
+    }
 }
 
 /// A command.
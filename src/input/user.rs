@@ -39,6 +39,11 @@ impl UserInput {
             KeyCode::Char('c') if event.modifiers == KeyModifiers::CONTROL => {
                 (Some(UserCommand::Exit), InputState::Empty)
             }
+            KeyCode::Char('e') if event.modifiers == KeyModifiers::CONTROL => {
+                (Some(UserCommand::RenderOnDemand), InputState::Empty)
+            }
+            KeyCode::Char('J') => (Some(UserCommand::ScrollDown), InputState::Empty),
+            KeyCode::Char('K') => (Some(UserCommand::ScrollUp), InputState::Empty),
             KeyCode::Char('G') => Self::apply_uppercase_g(state),
             KeyCode::Char('g') => Self::apply_lowercase_g(state),
             KeyCode::Char(number) if number.is_ascii_digit() => {
@@ -105,6 +110,15 @@ pub enum UserCommand {
     /// Jump to one particular slide.
     JumpSlide(u32),
 
+    /// Scroll down the current slide, if it doesn't fit in the screen.
+    ScrollDown,
+
+    /// Scroll up the current slide.
+    ScrollUp,
+
+    /// Render any on demand operations in the current slide, e.g. executing code blocks.
+    RenderOnDemand,
+
     /// Exit the presentation.
     Exit,
 }
@@ -156,4 +170,13 @@ mod test {
         assert_eq!(command, Some(UserCommand::JumpSlide(12)));
         assert_eq!(state, InputState::Empty);
     }
+
+    #[test]
+    fn scroll() {
+        let (command, _) = UserInput::apply_key_event(KeyCode::Char('J').into(), InputState::Empty);
+        assert_eq!(command, Some(UserCommand::ScrollDown));
+
+        let (command, _) = UserInput::apply_key_event(KeyCode::Char('K').into(), InputState::Empty);
+        assert_eq!(command, Some(UserCommand::ScrollUp));
+    }
 }
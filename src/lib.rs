@@ -3,12 +3,20 @@
 //! This is not meant to be used as a crate!
 
 pub mod builder;
+pub mod check;
 pub mod diff;
+pub mod execute;
+pub mod export;
 pub mod input;
+pub mod loader;
 pub mod markdown;
 pub mod presentation;
+#[cfg(unix)]
+pub mod presenter;
 pub mod render;
 pub mod resource;
 pub mod slideshow;
 pub mod style;
+#[cfg(unix)]
+pub mod sync;
 pub mod theme;
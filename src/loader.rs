@@ -0,0 +1,275 @@
+use crate::{
+    builder::{BuildError, PresentationBuilder},
+    markdown::{
+        elements::MarkdownElement,
+        parse::{MarkdownParser, ParseError},
+    },
+    presentation::Presentation,
+    render::highlighting::CodeHighlighter,
+    resource::Resources,
+    theme::PresentationTheme,
+};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Loads presentations from markdown files.
+pub struct PresentationLoader<'a> {
+    default_theme: &'a PresentationTheme,
+    default_highlighter: CodeHighlighter,
+    parser: MarkdownParser<'a>,
+    resources: Resources,
+}
+
+impl<'a> PresentationLoader<'a> {
+    /// Construct a new presentation loader.
+    pub fn new(
+        default_theme: &'a PresentationTheme,
+        default_highlighter: CodeHighlighter,
+        parser: MarkdownParser<'a>,
+        resources: Resources,
+    ) -> Self {
+        Self { default_theme, default_highlighter, parser, resources }
+    }
+
+    /// Load the presentation in the given path.
+    pub fn load(&mut self, path: &Path) -> Result<Presentation, LoadPresentationError> {
+        self.load_with_includes(path).map(|(presentation, _)| presentation)
+    }
+
+    /// Load the presentation in the given path, along with the paths of every file it includes.
+    pub fn load_with_includes(&mut self, path: &Path) -> Result<(Presentation, Vec<PathBuf>), LoadPresentationError> {
+        let contents = fs::read_to_string(path).map_err(LoadPresentationError::Reading)?;
+        let (elements, included_paths) = self.load_elements(&contents, path)?;
+        let presentation = self.build(elements)?;
+        Ok((presentation, included_paths))
+    }
+
+    /// Parse the contents of the presentation in the given path and resolve every include in it.
+    ///
+    /// This returns the resulting elements along with the paths of every file included.
+    pub fn load_elements(
+        &self,
+        contents: &str,
+        path: &Path,
+    ) -> Result<(Vec<MarkdownElement>, Vec<PathBuf>), LoadPresentationError> {
+        let elements = self.parser.parse(contents)?;
+        let mut resolver = IncludeResolver::new(&self.parser, self.resources.base_path()).with_root(path);
+        let elements = resolver.resolve(elements)?;
+        Ok((elements, resolver.into_included_paths()))
+    }
+
+    /// Build a presentation out of the given elements.
+    pub fn build(&mut self, elements: Vec<MarkdownElement>) -> Result<Presentation, BuildError> {
+        PresentationBuilder::new(self.default_highlighter.clone(), self.default_theme, &mut self.resources)
+            .build(elements)
+    }
+
+    /// Get the resources presentations are loaded with.
+    pub fn resources(&mut self) -> &mut Resources {
+        &mut self.resources
+    }
+}
+
+/// Replaces `<!-- include: path/to/file.md -->` comments with the elements in the files they point to.
+///
+/// Paths are relative to the given base path, which is the same one used for every other resource.
+struct IncludeResolver<'a, 'b> {
+    parser: &'b MarkdownParser<'a>,
+    base_path: &'b Path,
+    // The files currently being included, used to detect cycles.
+    including: Vec<PathBuf>,
+    // Every file included so far.
+    included: Vec<PathBuf>,
+}
+
+impl<'a, 'b> IncludeResolver<'a, 'b> {
+    /// Construct a new resolver.
+    fn new(parser: &'b MarkdownParser<'a>, base_path: &'b Path) -> Self {
+        Self { parser, base_path, including: Vec::new(), included: Vec::new() }
+    }
+
+    /// Set the path of the file the elements being resolved come from, so it can't be included.
+    fn with_root(mut self, path: &Path) -> Self {
+        self.including.push(path.canonicalize().unwrap_or_else(|_| path.into()));
+        self
+    }
+
+    /// Get the paths of every file included while resolving.
+    fn into_included_paths(self) -> Vec<PathBuf> {
+        self.included
+    }
+
+    /// Resolve all includes in the given elements, including any in the included files themselves.
+    fn resolve(&mut self, elements: Vec<MarkdownElement>) -> Result<Vec<MarkdownElement>, LoadPresentationError> {
+        let mut output = Vec::new();
+        for element in elements {
+            let path = match &element {
+                MarkdownElement::Comment(comment) => comment.strip_prefix("include:").map(str::trim),
+                _ => None,
+            };
+            match path {
+                Some(path) => {
+                    let elements = self.include(path).map_err(|error| LoadPresentationError::Include {
+                        path: path.into(),
+                        error: Box::new(error),
+                    })?;
+                    output.extend(elements);
+                }
+                None => output.push(element),
+            };
+        }
+        Ok(output)
+    }
+
+    fn include(&mut self, path: &str) -> Result<Vec<MarkdownElement>, LoadPresentationError> {
+        let path = self.base_path.join(path);
+        let canonical_path = path.canonicalize().map_err(LoadPresentationError::Reading)?;
+        if self.including.contains(&canonical_path) {
+            return Err(LoadPresentationError::IncludeCycle);
+        }
+        let contents = fs::read_to_string(&path).map_err(LoadPresentationError::Reading)?;
+        let elements = self.parser.parse(&contents)?;
+        if let Some(MarkdownElement::FrontMatter(_)) = elements.first() {
+            return Err(LoadPresentationError::IncludedFrontMatter);
+        }
+
+        if !self.included.contains(&canonical_path) {
+            self.included.push(canonical_path.clone());
+        }
+        self.including.push(canonical_path);
+        let elements = self.resolve(elements);
+        self.including.pop();
+        elements
+    }
+}
+
+/// An error when loading a presentation.
+#[derive(thiserror::Error, Debug)]
+pub enum LoadPresentationError {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+
+    #[error("reading presentation: {0}")]
+    Reading(io::Error),
+
+    #[error(transparent)]
+    Processing(#[from] BuildError),
+
+    #[error("in included file '{path}': {error}")]
+    Include { path: String, error: Box<LoadPresentationError> },
+
+    #[error("file includes itself")]
+    IncludeCycle,
+
+    #[error("included files can't have a front matter")]
+    IncludedFrontMatter,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::markdown::elements::Text;
+    use comrak::Arena;
+    use std::{env, process};
+
+    struct TestDirectory(PathBuf);
+
+    impl TestDirectory {
+        fn new(name: &str, files: &[(&str, &str)]) -> Self {
+            let path = env::temp_dir().join(format!("presenterm-test-{name}-{}", process::id()));
+            fs::create_dir_all(&path).expect("creating directory failed");
+            for (name, contents) in files {
+                fs::write(path.join(name), contents).expect("writing file failed");
+            }
+            Self(path)
+        }
+    }
+
+    impl Drop for TestDirectory {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn resolve(directory: &TestDirectory, contents: &str) -> Result<Vec<MarkdownElement>, LoadPresentationError> {
+        let arena = Arena::new();
+        let parser = MarkdownParser::new(&arena);
+        let elements = parser.parse(contents).expect("parse failed");
+        IncludeResolver::new(&parser, &directory.0).with_root(&directory.0.join("main.md")).resolve(elements)
+    }
+
+    #[test]
+    fn nested_includes() {
+        let directory = TestDirectory::new(
+            "nested",
+            &[("first.md", "# first\n\n<!-- include: second.md -->"), ("second.md", "# second")],
+        );
+        let elements = resolve(&directory, "# main\n\n<!-- include: first.md -->\n\n<!-- end_slide -->").unwrap();
+        let expected = vec![
+            MarkdownElement::Heading { level: 1, text: Text::from("main") },
+            MarkdownElement::Heading { level: 1, text: Text::from("first") },
+            MarkdownElement::Heading { level: 1, text: Text::from("second") },
+            MarkdownElement::Comment("end_slide".into()),
+        ];
+        assert_eq!(elements, expected);
+    }
+
+    #[test]
+    fn include_cycle() {
+        let directory = TestDirectory::new(
+            "cycle",
+            &[("first.md", "<!-- include: second.md -->"), ("second.md", "<!-- include: first.md -->")],
+        );
+        let error = resolve(&directory, "<!-- include: first.md -->").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "in included file 'first.md': in included file 'second.md': in included file 'first.md': file includes itself"
+        );
+    }
+
+    #[test]
+    fn self_include() {
+        let directory = TestDirectory::new("self", &[("main.md", "<!-- include: main.md -->")]);
+        let error = resolve(&directory, "<!-- include: main.md -->").unwrap_err();
+        assert_eq!(error.to_string(), "in included file 'main.md': file includes itself");
+    }
+
+    #[test]
+    fn included_paths() {
+        let directory = TestDirectory::new(
+            "paths",
+            &[("first.md", "<!-- include: second.md -->\n\n<!-- include: second.md -->"), ("second.md", "# second")],
+        );
+        let arena = Arena::new();
+        let parser = MarkdownParser::new(&arena);
+        let elements = parser.parse("<!-- include: first.md -->").expect("parse failed");
+        let mut resolver = IncludeResolver::new(&parser, &directory.0);
+        resolver.resolve(elements).expect("resolve failed");
+
+        let expected: Vec<_> =
+            ["first.md", "second.md"].iter().map(|name| directory.0.join(name).canonicalize().unwrap()).collect();
+        assert_eq!(resolver.into_included_paths(), expected);
+    }
+
+    #[test]
+    fn errors_in_included_files() {
+        let directory = TestDirectory::new("errors", &[("broken.md", "hi\n\n<div>bye</div>")]);
+        let error = resolve(&directory, "<!-- include: broken.md -->").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "in included file 'broken.md': parse error at 3:1: unsupported element: html block"
+        );
+
+        let error = resolve(&directory, "<!-- include: missing.md -->").unwrap_err();
+        assert!(matches!(error, LoadPresentationError::Include { .. }), "{error:?}");
+    }
+
+    #[test]
+    fn included_front_matter() {
+        let directory = TestDirectory::new("front-matter", &[("part.md", "---\ntitle: hi\n---\n\nbye")]);
+        let error = resolve(&directory, "<!-- include: part.md -->").unwrap_err();
+        assert_eq!(error.to_string(), "in included file 'part.md': included files can't have a front matter");
+    }
+}
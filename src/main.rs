@@ -1,28 +1,88 @@
-use clap::{error::ErrorKind, CommandFactory, Parser};
+use clap::{error::ErrorKind, CommandFactory, Parser, Subcommand};
 use comrak::Arena;
 use presenterm::{
+    check::PresentationChecker,
+    export::{html::HtmlExporter, pdf::PdfExporter, SlideRenderer, EXPORT_WINDOW_SIZE},
     input::source::CommandSource,
+    loader::PresentationLoader,
     markdown::parse::MarkdownParser,
-    render::highlighting::CodeHighlighter,
+    render::{highlighting::CodeHighlighter, properties::WindowSize},
     resource::Resources,
     slideshow::{SlideShow, SlideShowMode},
     theme::PresentationTheme,
 };
-use std::path::{Path, PathBuf};
+#[cfg(unix)]
+use presenterm::{
+    presenter::PresenterView,
+    sync::{SlidePublisher, SlideSubscriber, socket_path},
+};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+};
 
 /// Run slideshows from your terminal.
 #[derive(Parser)]
+#[clap(args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
 struct Cli {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
     /// The path to the markdown file that contains the presentation.
-    path: PathBuf,
+    #[clap(required = true)]
+    path: Option<PathBuf>,
 
     /// Whether to use presentation mode.
     #[clap(short, long, default_value_t = false)]
     present: bool,
 
     /// The theme to use.
-    #[clap(short, long, default_value = "dark")]
+    #[clap(short, long, default_value = "dark", global = true)]
     theme: String,
+
+    /// Run the presenter view for a presentation that's already running.
+    ///
+    /// This displays the current slide's speaker notes, a preview of the next slide, and a timer.
+    #[cfg(unix)]
+    #[clap(long, default_value_t = false, conflicts_with = "dump_slide")]
+    presenter_view: bool,
+
+    /// Export the presentation as a PDF file.
+    ///
+    /// The file is written next to the presentation, using the same name and a `.pdf` extension.
+    #[clap(long, default_value_t = false)]
+    export_pdf: bool,
+
+    /// Export the presentation as a self contained HTML file.
+    ///
+    /// The file is written next to the presentation, using the same name and a `.html` extension.
+    #[clap(long, default_value_t = false, conflicts_with = "export_pdf")]
+    export_html: bool,
+
+    /// Print the contents of the given slide number, rendered without a terminal, and exit.
+    ///
+    /// This is meant to be used to compare slides against a known good version of them.
+    #[clap(long, conflicts_with_all = ["export_pdf", "export_html"])]
+    dump_slide: Option<usize>,
+
+    /// The window size to use when dumping a slide, in the `<columns>x<rows>` format.
+    #[clap(long, default_value = "80x24", requires = "dump_slide")]
+    size: WindowSize,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Check a presentation for problems without displaying it.
+    ///
+    /// This exits with a non zero status if any errors are found.
+    Check {
+        /// The path to the markdown file that contains the presentation.
+        path: PathBuf,
+
+        /// The window size slides must fit in, in the `<columns>x<rows>` format.
+        #[clap(long, default_value = "80x24")]
+        size: WindowSize,
+    },
 }
 
 fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
@@ -40,12 +100,66 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
     let arena = Arena::new();
     let parser = MarkdownParser::new(&arena);
     let default_highlighter = CodeHighlighter::new("base16-ocean.dark")?;
-    let resources_path = cli.path.parent().unwrap_or(Path::new("/"));
+    if let Some(Command::Check { path, size }) = cli.command {
+        let resources = Resources::new(path.parent().unwrap_or(Path::new("/")));
+        let loader = PresentationLoader::new(&default_theme, default_highlighter, parser, resources);
+        let mut checker = PresentationChecker::new(loader, size);
+        let issues = checker.check(&path)?;
+        for issue in &issues {
+            let severity = if issue.is_warning() { "warning" } else { "error" };
+            println!("{}: {severity}: {issue}", path.display());
+        }
+        if issues.iter().any(|issue| !issue.is_warning()) {
+            return Err("presentation has errors".into());
+        }
+        return Ok(());
+    }
+    let path = cli.path.expect("no path");
+    let resources_path = path.parent().unwrap_or(Path::new("/"));
     let resources = Resources::new(resources_path);
-    let commands = CommandSource::new(&cli.path);
+    let mut loader = PresentationLoader::new(&default_theme, default_highlighter, parser, resources);
+    if let Some(slide) = cli.dump_slide {
+        let presentation = loader.load(&path)?;
+        let index = slide.checked_sub(1).ok_or("slide numbers start at 1")?;
+        let grid = SlideRenderer::new(cli.size).render_slide_at(&presentation, index)?;
+        for row in 0..grid.row_count() {
+            println!("{}", grid.row_text(row).trim_end());
+        }
+        return Ok(());
+    }
+    if cli.export_pdf || cli.export_html {
+        let presentation = loader.load(&path)?;
+        let title = path.file_stem().unwrap_or_default().to_string_lossy();
+        let output_path = path.with_extension(if cli.export_pdf { "pdf" } else { "html" });
+        let output = File::create(&output_path)?;
+        if cli.export_pdf {
+            PdfExporter::new(EXPORT_WINDOW_SIZE).export(&presentation, &title, output)?;
+        } else {
+            HtmlExporter::new(EXPORT_WINDOW_SIZE).export(&presentation, &title, output)?;
+        }
+        println!("Presentation exported to {}", output_path.display());
+        return Ok(());
+    }
+
+    #[cfg(unix)]
+    if cli.presenter_view {
+        let subscriber = socket_path(&path)
+            .and_then(SlideSubscriber::connect)
+            .map_err(|e| format!("connecting to presentation (is it running?): {e}"))?;
+        let view = PresenterView::new(loader, subscriber, mode);
+        view.present(&path)?;
+        return Ok(());
+    }
 
-    let slideshow = SlideShow::new(&default_theme, default_highlighter, commands, parser, resources, mode);
-    slideshow.present(&cli.path)?;
+    let commands = CommandSource::new(&path);
+    let slideshow = SlideShow::new(loader, commands, mode);
+    // The presentation works just fine without a presenter view so don't fail if we can't publish.
+    #[cfg(unix)]
+    let slideshow = match socket_path(&path).and_then(SlidePublisher::new) {
+        Ok(publisher) => slideshow.with_publisher(publisher),
+        Err(e) => slideshow.with_warning(format!("presenter view won't be available: {e}")),
+    };
+    slideshow.present(&path)?;
     Ok(())
 }
 
@@ -53,5 +167,6 @@ fn main() {
     let cli = Cli::parse();
     if let Err(e) = run(cli) {
         eprintln!("Failed to run presentation: {e}");
+        std::process::exit(1);
     }
 }
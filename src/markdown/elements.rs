@@ -1,13 +1,13 @@
 use unicode_width::UnicodeWidthStr;
 
 use crate::style::TextStyle;
-use std::{iter, path::PathBuf};
+use std::{iter, ops::RangeInclusive, path::PathBuf};
 
 /// A markdown element.
 ///
 /// This represents each of the supported markdown elements. The structure here differs a bit from
 /// the spec, mostly in how inlines are handled, to simplify its processing.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum MarkdownElement {
     /// The front matter that optionally shows up at the beginning of the file.
     FrontMatter(String),
@@ -41,8 +41,19 @@ pub enum MarkdownElement {
     /// An HTML comment.
     Comment(String),
 
-    /// A quote.
-    BlockQuote(Vec<String>),
+    /// A quote, which can contain paragraphs, headings, lists, code and other quotes.
+    BlockQuote(Vec<MarkdownElement>),
+
+    /// An alert, which is a block quote that starts with a marker like `[!NOTE]`.
+    ///
+    /// Alerts can contain the same elements as block quotes and optionally have an explicit
+    /// title, as in `[!TIP] My title`.
+    Alert { alert_type: AlertType, title: Option<String>, elements: Vec<MarkdownElement> },
+
+    /// The definition of a footnote.
+    ///
+    /// These always show up at the end of the document, regardless of where they were defined.
+    FootnoteDefinition { name: String, text: Text },
 }
 
 /// The components that make up a paragraph.
@@ -95,24 +106,36 @@ impl<T: Into<StyledText>> From<T> for Text {
 pub struct StyledText {
     pub text: String,
     pub style: TextStyle,
+
+    /// The theme class this text's colors should be taken from, if any.
+    pub class: Option<String>,
+
+    /// The name of the footnote this text is a reference to, if any.
+    pub footnote: Option<String>,
 }
 
 impl StyledText {
     /// Construct a new styled text.
     pub fn new<S: Into<String>>(text: S, style: TextStyle) -> Self {
-        Self { text: text.into(), style }
+        Self { text: text.into(), style, class: None, footnote: None }
+    }
+
+    /// Construct a reference to a footnote.
+    pub fn footnote_reference<S: Into<String>>(name: S) -> Self {
+        let name = name.into();
+        Self { text: name.clone(), style: TextStyle::default(), class: None, footnote: Some(name) }
     }
 }
 
 impl From<String> for StyledText {
     fn from(text: String) -> Self {
-        Self { text, style: TextStyle::default() }
+        Self { text, style: TextStyle::default(), class: None, footnote: None }
     }
 }
 
 impl From<&str> for StyledText {
     fn from(text: &str) -> Self {
-        Self { text: text.into(), style: TextStyle::default() }
+        Self { text: text.into(), style: TextStyle::default(), class: None, footnote: None }
     }
 }
 
@@ -129,6 +152,12 @@ pub struct ListItem {
 
     /// The type of list item.
     pub item_type: ListItemType,
+
+    /// The blocks that follow this item's contents, like code blocks or extra paragraphs.
+    pub blocks: Vec<MarkdownElement>,
+
+    /// Whether this item's checkbox is checked, if this is a task list item.
+    pub checkbox: Option<bool>,
 }
 
 /// The type of a list item.
@@ -138,10 +167,34 @@ pub enum ListItemType {
     Unordered,
 
     /// A list item for an ordered list that uses parenthesis after the list item number.
-    OrderedParens(u16),
+    OrderedParens(u32),
 
     /// A list item for an ordered list that uses a period after the list item number.
-    OrderedPeriod(u16),
+    OrderedPeriod(u32),
+
+    /// The continuation of the previous item in the same depth after a nested list.
+    ///
+    /// This has no marker of its own.
+    Continuation,
+}
+
+/// The type of an alert.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AlertType {
+    /// Information users should be aware of.
+    Note,
+
+    /// Helpful advice.
+    Tip,
+
+    /// Key information users need to know.
+    Important,
+
+    /// Something that needs users' immediate attention.
+    Warning,
+
+    /// Risks or negative outcomes of certain actions.
+    Caution,
 }
 
 /// A piece of code.
@@ -152,6 +205,63 @@ pub struct Code {
 
     /// The programming language this code is written in.
     pub language: ProgrammingLanguage,
+
+    /// The name of the language as written in the code block, e.g. `rs` or `potato`.
+    pub language_name: String,
+
+    /// The attributes used for this code block.
+    pub attributes: CodeAttributes,
+}
+
+/// The attributes of a code block.
+///
+/// These are specified after the language in the code block's info string, e.g. `bash +exec`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CodeAttributes {
+    /// Whether this code block can be executed during the presentation.
+    pub execute: bool,
+
+    /// Whether to show line numbers next to every line in this code block.
+    pub line_numbers: bool,
+
+    /// The groups of lines to be highlighted.
+    ///
+    /// Every group is highlighted in a separate step of the presentation, in order. If this is
+    /// empty, all lines are highlighted.
+    pub highlight_groups: Vec<HighlightGroup>,
+}
+
+/// A group of lines to be highlighted in a code block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HighlightGroup(Vec<Highlight>);
+
+impl HighlightGroup {
+    /// Construct a new group out of the given highlights.
+    pub fn new(highlights: Vec<Highlight>) -> Self {
+        Self(highlights)
+    }
+
+    /// Checks whether the given line, starting from 1, is highlighted in this group.
+    pub fn contains(&self, line: u16) -> bool {
+        self.0.iter().any(|highlight| match highlight {
+            Highlight::All => true,
+            Highlight::Single(number) => *number == line,
+            Highlight::Range(range) => range.contains(&line),
+        })
+    }
+}
+
+/// A highlighted set of lines.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Highlight {
+    /// All lines are highlighted.
+    All,
+
+    /// A single line is highlighted.
+    Single(u16),
+
+    /// An inclusive range of lines is highlighted.
+    Range(RangeInclusive<u16>),
 }
 
 /// A programming language.
@@ -200,6 +310,9 @@ pub struct Table {
 
     /// All of the rows in this table, excluding the header.
     pub rows: Vec<TableRow>,
+
+    /// The alignment of the contents of each column.
+    pub alignments: Vec<ColumnAlignment>,
 }
 
 impl Table {
@@ -218,6 +331,20 @@ impl Table {
     }
 }
 
+/// The alignment of the contents of a table column.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum ColumnAlignment {
+    /// Align to the left.
+    #[default]
+    Left,
+
+    /// Align to the center.
+    Center,
+
+    /// Align to the right.
+    Right,
+}
+
 /// A table row.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TableRow(pub Vec<Text>);
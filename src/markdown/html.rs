@@ -0,0 +1,155 @@
+use crate::theme::Colors;
+use crossterm::style::Color;
+use std::str::FromStr;
+
+/// A piece of inline HTML.
+///
+/// Only `<span>` tags are supported, which can be used to set the colors of the text within them,
+/// either directly via a `style` attribute or by referencing a class defined in the theme.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) enum HtmlInline {
+    OpenSpan(Span),
+    CloseSpan,
+}
+
+/// The attributes in a `<span>` tag.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub(crate) struct Span {
+    pub(crate) colors: Colors,
+    pub(crate) class: Option<String>,
+}
+
+impl FromStr for HtmlInline {
+    type Err = ParseHtmlError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s == "</span>" {
+            return Ok(Self::CloseSpan);
+        }
+        let attributes = s
+            .strip_prefix("<span")
+            .and_then(|s| s.strip_suffix('>'))
+            .ok_or_else(|| ParseHtmlError::UnsupportedTag(s.into()))?;
+        if !attributes.is_empty() && !attributes.starts_with(char::is_whitespace) {
+            return Err(ParseHtmlError::UnsupportedTag(s.into()));
+        }
+        let mut span = Span::default();
+        let mut input = attributes.trim_start();
+        while !input.is_empty() {
+            let (name, value, rest) = Self::parse_attribute(input)?;
+            match name {
+                "style" => span.colors = Self::parse_style(value)?,
+                "class" => span.class = Some(value.trim().into()),
+                _ => return Err(ParseHtmlError::UnsupportedAttribute(name.into())),
+            };
+            input = rest.trim_start();
+        }
+        Ok(Self::OpenSpan(span))
+    }
+}
+
+impl HtmlInline {
+    fn parse_attribute(input: &str) -> Result<(&str, &str, &str), ParseHtmlError> {
+        let (name, rest) = input.split_once('=').ok_or_else(|| ParseHtmlError::InvalidAttribute(input.into()))?;
+        let rest = rest.trim_start();
+        let quote = rest.chars().next().filter(|c| *c == '"' || *c == '\'');
+        let quote = quote.ok_or_else(|| ParseHtmlError::InvalidAttribute(input.into()))?;
+        let (value, rest) =
+            rest[1..].split_once(quote).ok_or_else(|| ParseHtmlError::InvalidAttribute(input.into()))?;
+        Ok((name.trim(), value, rest))
+    }
+
+    fn parse_style(style: &str) -> Result<Colors, ParseHtmlError> {
+        let mut colors = Colors::default();
+        for property in style.split(';').map(str::trim).filter(|property| !property.is_empty()) {
+            let (name, value) =
+                property.split_once(':').ok_or_else(|| ParseHtmlError::InvalidStyle(property.into()))?;
+            let color = Self::parse_color(value.trim())?;
+            match name.trim() {
+                "color" => colors.foreground = Some(color),
+                "background-color" => colors.background = Some(color),
+                name => return Err(ParseHtmlError::UnsupportedStyle(name.into())),
+            };
+        }
+        Ok(colors)
+    }
+
+    fn parse_color(input: &str) -> Result<Color, ParseHtmlError> {
+        let invalid = || ParseHtmlError::InvalidColor(input.into());
+        if let Some(hex) = input.strip_prefix('#') {
+            if hex.len() != 6 || !hex.is_ascii() {
+                return Err(invalid());
+            }
+            let component = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).map_err(|_| invalid());
+            return Ok(Color::Rgb { r: component(0)?, g: component(2)?, b: component(4)? });
+        }
+        Color::try_from(input).map_err(|_| invalid())
+    }
+}
+
+/// An error parsing inline HTML.
+#[derive(thiserror::Error, Debug)]
+pub enum ParseHtmlError {
+    #[error("unsupported tag '{0}', only <span> is supported")]
+    UnsupportedTag(String),
+
+    #[error("unsupported attribute '{0}'")]
+    UnsupportedAttribute(String),
+
+    #[error("invalid attribute '{0}'")]
+    InvalidAttribute(String),
+
+    #[error("invalid style '{0}'")]
+    InvalidStyle(String),
+
+    #[error("unsupported style property '{0}'")]
+    UnsupportedStyle(String),
+
+    #[error("invalid color '{0}'")]
+    InvalidColor(String),
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use rstest::rstest;
+
+    #[test]
+    fn close_span() {
+        assert_eq!("</span>".parse::<HtmlInline>().unwrap(), HtmlInline::CloseSpan);
+    }
+
+    #[rstest]
+    #[case::empty("<span>", Span::default())]
+    #[case::named_color(
+        "<span style=\"color: red\">",
+        Span { colors: Colors { foreground: Some(Color::Red), background: None }, class: None }
+    )]
+    #[case::hex_colors(
+        "<span style='color: #ff0000; background-color: #00ff00;'>",
+        Span {
+            colors: Colors {
+                foreground: Some(Color::Rgb { r: 255, g: 0, b: 0 }),
+                background: Some(Color::Rgb { r: 0, g: 255, b: 0 }),
+            },
+            class: None
+        }
+    )]
+    #[case::class("<span class=\"warning\">", Span { colors: Default::default(), class: Some("warning".into()) })]
+    fn open_span(#[case] input: &str, #[case] expected: Span) {
+        assert_eq!(input.parse::<HtmlInline>().unwrap(), HtmlInline::OpenSpan(expected));
+    }
+
+    #[rstest]
+    #[case::other_tag("<div>")]
+    #[case::span_prefix("<spanner>")]
+    #[case::unknown_attribute("<span id=\"foo\">")]
+    #[case::unquoted_attribute("<span class=foo>")]
+    #[case::unknown_property("<span style=\"font-size: 10px\">")]
+    #[case::invalid_color("<span style=\"color: potato\">")]
+    #[case::invalid_hex_color("<span style=\"color: #ff\">")]
+    fn invalid_html(#[case] input: &str) {
+        assert!(input.parse::<HtmlInline>().is_err());
+    }
+}
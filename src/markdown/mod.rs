@@ -1,3 +1,4 @@
 pub mod elements;
+pub(crate) mod html;
 pub mod parse;
 pub mod text;
@@ -1,20 +1,22 @@
 use crate::{
-    markdown::elements::{
-        Code, ListItem, ListItemType, MarkdownElement, ParagraphElement, ProgrammingLanguage, StyledText, Table,
-        TableRow, Text,
+    markdown::{
+        elements::{
+            AlertType, Code, CodeAttributes, ColumnAlignment, Highlight, HighlightGroup, ListItem, ListItemType,
+            MarkdownElement, ParagraphElement, ProgrammingLanguage, StyledText, Table, TableRow, Text,
+        },
+        html::{HtmlInline, ParseHtmlError, Span},
     },
     style::TextStyle,
 };
 use comrak::{
-    format_commonmark,
     nodes::{
         AstNode, ListDelimType, ListType, NodeCodeBlock, NodeHeading, NodeHtmlBlock, NodeList, NodeValue, Sourcepos,
+        TableAlignment,
     },
-    parse_document, Arena, ComrakOptions, ListStyleType,
+    parse_document, Arena, ComrakOptions,
 };
 use std::{
     fmt::{self, Debug, Display},
-    io::BufWriter,
     mem,
 };
 
@@ -29,6 +31,8 @@ impl Default for ParserOptions {
         options.extension.front_matter_delimiter = Some("---".into());
         options.extension.table = true;
         options.extension.strikethrough = true;
+        options.extension.footnotes = true;
+        options.extension.tasklist = true;
         Self(options)
     }
 }
@@ -66,14 +70,15 @@ impl<'a> MarkdownParser<'a> {
             NodeValue::FrontMatter(contents) => Self::parse_front_matter(contents)?,
             NodeValue::Heading(heading) => Self::parse_heading(heading, node)?,
             NodeValue::List(list) => {
-                let items = Self::parse_list(node, list.marker_offset as u8 / 2)?;
+                let items = Self::parse_list(list, node, list.marker_offset as u8 / 2)?;
                 MarkdownElement::List(items)
             }
-            NodeValue::Table(_) => Self::parse_table(node)?,
+            NodeValue::Table(alignments) => Self::parse_table(alignments, node)?,
             NodeValue::CodeBlock(block) => Self::parse_code_block(block, data.sourcepos)?,
             NodeValue::ThematicBreak => MarkdownElement::ThematicBreak,
             NodeValue::HtmlBlock(block) => Self::parse_html_block(block, data.sourcepos)?,
             NodeValue::BlockQuote => Self::parse_block_quote(node)?,
+            NodeValue::FootnoteDefinition(name) => Self::parse_footnote_definition(name, node)?,
             other => return Err(ParseErrorKind::UnsupportedElement(other.identifier()).with_sourcepos(data.sourcepos)),
         };
         Ok(vec![element])
@@ -102,30 +107,116 @@ impl<'a> MarkdownParser<'a> {
     }
 
     fn parse_block_quote(node: &'a AstNode<'a>) -> ParseResult<MarkdownElement> {
-        let mut buffer = BufWriter::new(Vec::new());
-        let mut options = ParserOptions::default().0;
-        options.render.list_style = ListStyleType::Star;
-        format_commonmark(node, &options, &mut buffer)
-            .map_err(|e| ParseErrorKind::Internal(e.to_string()).with_sourcepos(node.data.borrow().sourcepos))?;
-
-        let buffer = buffer.into_inner().expect("unwrapping writer failed");
-        let mut lines = Vec::new();
-        for line in String::from_utf8_lossy(&buffer).lines() {
-            let line = match line.find('>') {
-                Some(index) => line[index + 1..].trim(),
-                None => line,
+        let alert = Self::parse_alert_header(node);
+        let elements = Self::parse_block_quote_elements(node)?;
+        match alert {
+            Some((alert_type, title)) => Ok(MarkdownElement::Alert { alert_type, title, elements }),
+            None => Ok(MarkdownElement::BlockQuote(elements)),
+        }
+    }
+
+    // Alerts are block quotes whose first line looks like `[!NOTE]`. If this is one, that line is
+    // removed from the quote so it isn't displayed as part of its text.
+    fn parse_alert_header(node: &'a AstNode<'a>) -> Option<(AlertType, Option<String>)> {
+        let paragraph = node.first_child().filter(|child| matches!(child.data.borrow().value, NodeValue::Paragraph))?;
+        let mut header = String::new();
+        let mut header_nodes = Vec::new();
+        for inline in paragraph.children() {
+            header_nodes.push(inline);
+            match &inline.data.borrow().value {
+                NodeValue::Text(text) => header.push_str(text),
+                NodeValue::SoftBreak | NodeValue::LineBreak => break,
+                _ => return None,
+            };
+        }
+        let (name, title) = header.trim().strip_prefix("[!")?.split_once(']')?;
+        let alert_type = Self::parse_alert_type(name)?;
+        let title = Some(title.trim()).filter(|title| !title.is_empty()).map(String::from);
+        for inline in header_nodes {
+            inline.detach();
+        }
+        if paragraph.first_child().is_none() {
+            paragraph.detach();
+        }
+        Some((alert_type, title))
+    }
+
+    fn parse_alert_type(name: &str) -> Option<AlertType> {
+        let alert_type = match name.to_lowercase().as_str() {
+            "note" => AlertType::Note,
+            "tip" => AlertType::Tip,
+            "important" => AlertType::Important,
+            "warning" => AlertType::Warning,
+            "caution" => AlertType::Caution,
+            _ => return None,
+        };
+        Some(alert_type)
+    }
+
+    fn parse_block_quote_elements(node: &'a AstNode<'a>) -> ParseResult<Vec<MarkdownElement>> {
+        let mut elements = Vec::new();
+        for node in node.children() {
+            let data = node.data.borrow();
+            let supported = matches!(
+                data.value,
+                NodeValue::Paragraph
+                    | NodeValue::Heading(_)
+                    | NodeValue::List(_)
+                    | NodeValue::CodeBlock(_)
+                    | NodeValue::BlockQuote
+            );
+            if !supported {
+                return Err(ParseErrorKind::UnsupportedStructure {
+                    container: "block quote",
+                    element: data.value.identifier(),
+                }
+                .with_sourcepos(data.sourcepos));
+            }
+            for element in Self::parse_node(node)? {
+                if let MarkdownElement::Image(_) = element {
+                    return Err(ParseErrorKind::UnsupportedStructure { container: "block quote", element: "image" }
+                        .with_sourcepos(data.sourcepos));
+                }
+                elements.push(element);
+            }
+        }
+        Ok(elements)
+    }
+
+    fn parse_footnote_definition(name: &str, node: &'a AstNode<'a>) -> ParseResult<MarkdownElement> {
+        let mut text = Text { chunks: Vec::new() };
+        for node in node.children() {
+            let data = node.data.borrow();
+            let NodeValue::Paragraph = &data.value else {
+                return Err(ParseErrorKind::UnsupportedStructure {
+                    container: "footnote",
+                    element: data.value.identifier(),
+                }
+                .with_sourcepos(data.sourcepos));
             };
-            lines.push(line.to_string());
+            // Footnotes are displayed in a single line so paragraphs are simply joined together.
+            if !text.chunks.is_empty() {
+                text.chunks.push(StyledText::from(" "));
+            }
+            text.chunks.extend(Self::parse_text(node)?.chunks);
         }
-        Ok(MarkdownElement::BlockQuote(lines))
+        Ok(MarkdownElement::FootnoteDefinition { name: name.into(), text })
     }
 
     fn parse_code_block(block: &NodeCodeBlock, sourcepos: Sourcepos) -> ParseResult<MarkdownElement> {
         if !block.fenced {
             return Err(ParseErrorKind::UnfencedCodeBlock.with_sourcepos(sourcepos));
         }
+        let (language_name, attributes) =
+            Self::parse_block_info(&block.info).map_err(|e| e.with_sourcepos(sourcepos))?;
+        let language = Self::parse_programming_language(language_name);
+        let code = Code { contents: block.literal.clone(), language, language_name: language_name.into(), attributes };
+        Ok(MarkdownElement::Code(code))
+    }
+
+    fn parse_programming_language(language: &str) -> ProgrammingLanguage {
         use ProgrammingLanguage::*;
-        let language = match block.info.as_str() {
+        match language {
             "asp" => Asp,
             "bash" => Bash,
             "c" => C,
@@ -158,9 +249,71 @@ impl<'a> MarkdownParser<'a> {
             "xml" => Xml,
             "yaml" => Yaml,
             _ => Unknown,
-        };
-        let code = Code { contents: block.literal.clone(), language };
-        Ok(MarkdownElement::Code(code))
+        }
+    }
+
+    fn parse_block_info(info: &str) -> Result<(&str, CodeAttributes), ParseErrorKind> {
+        let info = info.trim_start();
+        let (language, mut attributes_input) = info.split_once(char::is_whitespace).unwrap_or((info, ""));
+        // Anything after a comma is meant for other tools, like in `rust,ignore`.
+        let language = language.split(',').next().unwrap_or_default();
+        let mut attributes = CodeAttributes::default();
+        loop {
+            attributes_input = attributes_input.trim_start();
+            if attributes_input.is_empty() {
+                break;
+            }
+            // Highlighted lines can contain spaces, e.g. `{1, 3}`, so these go until the closing brace.
+            if let Some(input) = attributes_input.strip_prefix('{') {
+                let (groups, rest) = input
+                    .split_once('}')
+                    .ok_or_else(|| ParseErrorKind::InvalidHighlightedLines(attributes_input.into()))?;
+                if !attributes.highlight_groups.is_empty() {
+                    return Err(ParseErrorKind::InvalidHighlightedLines(groups.into()));
+                }
+                attributes.highlight_groups = Self::parse_highlight_groups(groups)?;
+                attributes_input = rest;
+                continue;
+            }
+            let (attribute, rest) = attributes_input.split_once(char::is_whitespace).unwrap_or((attributes_input, ""));
+            // Only attributes prefixed with a `+` are ours, anything else is meant for other tools.
+            match attribute {
+                "+exec" => attributes.execute = true,
+                "+line_numbers" => attributes.line_numbers = true,
+                _ if attribute.starts_with('+') => return Err(ParseErrorKind::InvalidCodeAttribute(attribute.into())),
+                _ => (),
+            };
+            attributes_input = rest;
+        }
+        Ok((language, attributes))
+    }
+
+    fn parse_highlight_groups(input: &str) -> Result<Vec<HighlightGroup>, ParseErrorKind> {
+        let mut groups = Vec::new();
+        for group in input.split('|') {
+            let mut highlights = Vec::new();
+            for highlight in group.split(',') {
+                let highlight = Self::parse_highlight(highlight.trim())
+                    .ok_or_else(|| ParseErrorKind::InvalidHighlightedLines(input.into()))?;
+                highlights.push(highlight);
+            }
+            groups.push(HighlightGroup::new(highlights));
+        }
+        Ok(groups)
+    }
+
+    fn parse_highlight(input: &str) -> Option<Highlight> {
+        if input == "all" {
+            return Some(Highlight::All);
+        }
+        let parse_line = |line: &str| line.trim().parse::<u16>().ok().filter(|line| *line > 0);
+        match input.split_once('-') {
+            Some((start, end)) => {
+                let (start, end) = (parse_line(start)?, parse_line(end)?);
+                (start <= end).then_some(Highlight::Range(start..=end))
+            }
+            None => parse_line(input).map(Highlight::Single),
+        }
     }
 
     fn parse_heading(heading: &NodeHeading, node: &'a AstNode<'a>) -> ParseResult<MarkdownElement> {
@@ -209,14 +362,22 @@ impl<'a> MarkdownParser<'a> {
         Ok(Text { chunks })
     }
 
-    fn parse_list(root: &'a AstNode<'a>, depth: u8) -> ParseResult<Vec<ListItem>> {
+    fn parse_list(list: &NodeList, root: &'a AstNode<'a>, depth: u8) -> ParseResult<Vec<ListItem>> {
         let mut elements = Vec::new();
         for (index, node) in root.children().enumerate() {
-            let number = (index + 1) as u16;
+            // Ordered lists are numbered starting from the first item's number, which has at most 9
+            // digits so this only saturates for absurdly long lists.
+            let number = u32::try_from(list.start.saturating_add(index)).unwrap_or(u32::MAX);
             let data = node.data.borrow();
             match &data.value {
                 NodeValue::Item(item) => {
-                    elements.extend(Self::parse_list_item(item, node, depth, number)?);
+                    let item_type = Self::list_item_type(item, number);
+                    elements.extend(Self::parse_list_item(item_type, None, node, depth)?);
+                }
+                // Task items are numbered just like the rest of the items in the list they're in.
+                NodeValue::TaskItem(mark) => {
+                    let item_type = Self::list_item_type(list, number);
+                    elements.extend(Self::parse_list_item(item_type, Some(mark.is_some()), node, depth)?);
                 }
                 other => {
                     return Err(ParseErrorKind::UnsupportedStructure {
@@ -230,22 +391,49 @@ impl<'a> MarkdownParser<'a> {
         Ok(elements)
     }
 
-    fn parse_list_item(item: &NodeList, root: &'a AstNode<'a>, depth: u8, number: u16) -> ParseResult<Vec<ListItem>> {
-        let item_type = match (item.list_type, item.delimiter) {
+    fn list_item_type(item: &NodeList, number: u32) -> ListItemType {
+        match (item.list_type, item.delimiter) {
             (ListType::Bullet, _) => ListItemType::Unordered,
             (ListType::Ordered, ListDelimType::Paren) => ListItemType::OrderedParens(number),
             (ListType::Ordered, ListDelimType::Period) => ListItemType::OrderedPeriod(number),
-        };
+        }
+    }
+
+    fn parse_list_item(
+        item_type: ListItemType,
+        checkbox: Option<bool>,
+        root: &'a AstNode<'a>,
+        depth: u8,
+    ) -> ParseResult<Vec<ListItem>> {
         let mut elements = Vec::new();
+        let mut item =
+            ListItem { contents: Text { chunks: Vec::new() }, depth, item_type, blocks: Vec::new(), checkbox };
+        let is_empty = |item: &ListItem| item.contents.chunks.is_empty() && item.blocks.is_empty();
         for node in root.children() {
             let data = node.data.borrow();
             match &data.value {
-                NodeValue::Paragraph => {
-                    let contents = Self::parse_text(node)?;
-                    elements.push(ListItem { contents, depth, item_type: item_type.clone() });
+                // The first paragraph is the item's text and anything after it is a block within it.
+                NodeValue::Paragraph if is_empty(&item) => {
+                    let (contents, blocks) = Self::split_list_item_paragraph(Self::parse_paragraph(node)?);
+                    item.contents = contents;
+                    item.blocks = blocks;
                 }
-                NodeValue::List(_) => {
-                    elements.extend(Self::parse_list(node, depth + 1)?);
+                NodeValue::Paragraph => item.blocks.extend(Self::parse_paragraph(node)?),
+                NodeValue::CodeBlock(block) => item.blocks.push(Self::parse_code_block(block, data.sourcepos)?),
+                NodeValue::BlockQuote => item.blocks.push(Self::parse_block_quote(node)?),
+                NodeValue::List(list) => {
+                    let continuation = ListItem {
+                        contents: Text { chunks: Vec::new() },
+                        depth,
+                        item_type: ListItemType::Continuation,
+                        blocks: Vec::new(),
+                        checkbox: None,
+                    };
+                    let item = mem::replace(&mut item, continuation);
+                    if !is_empty(&item) {
+                        elements.push(item);
+                    }
+                    elements.extend(Self::parse_list(list, node, depth + 1)?);
                 }
                 other => {
                     return Err(ParseErrorKind::UnsupportedStructure {
@@ -256,10 +444,32 @@ impl<'a> MarkdownParser<'a> {
                 }
             }
         }
+        if !is_empty(&item) {
+            elements.push(item);
+        }
         Ok(elements)
     }
 
-    fn parse_table(node: &'a AstNode<'a>) -> ParseResult<MarkdownElement> {
+    // Only the text up to the first line break or image in the paragraph goes next to the item's
+    // marker, the rest of the paragraph is displayed below it.
+    fn split_list_item_paragraph(mut elements: Vec<MarkdownElement>) -> (Text, Vec<MarkdownElement>) {
+        let Some(MarkdownElement::Paragraph(paragraph)) = elements.first_mut() else {
+            return (Text { chunks: Vec::new() }, elements);
+        };
+        let Some(ParagraphElement::Text(_)) = paragraph.first() else {
+            return (Text { chunks: Vec::new() }, elements);
+        };
+        let ParagraphElement::Text(contents) = paragraph.remove(0) else { unreachable!() };
+        if matches!(paragraph.first(), Some(ParagraphElement::LineBreak)) {
+            paragraph.remove(0);
+        }
+        if paragraph.is_empty() {
+            elements.remove(0);
+        }
+        (contents, elements)
+    }
+
+    fn parse_table(alignments: &[TableAlignment], node: &'a AstNode<'a>) -> ParseResult<MarkdownElement> {
         let mut header = TableRow(Vec::new());
         let mut rows = Vec::new();
         for node in node.children() {
@@ -278,7 +488,15 @@ impl<'a> MarkdownParser<'a> {
                 rows.push(row)
             }
         }
-        Ok(MarkdownElement::Table(Table { header, rows }))
+        let alignments = alignments
+            .iter()
+            .map(|alignment| match alignment {
+                TableAlignment::None | TableAlignment::Left => ColumnAlignment::Left,
+                TableAlignment::Center => ColumnAlignment::Center,
+                TableAlignment::Right => ColumnAlignment::Right,
+            })
+            .collect();
+        Ok(MarkdownElement::Table(Table { header, rows, alignments }))
     }
 
     fn parse_table_row(node: &'a AstNode<'a>) -> ParseResult<TableRow> {
@@ -303,6 +521,8 @@ impl<'a> MarkdownParser<'a> {
 struct InlinesParser {
     inlines: Vec<Inline>,
     pending_text: Vec<StyledText>,
+    // Spans are opened and closed in sibling nodes so keep track of the ones we're in.
+    spans: Vec<Span>,
 }
 
 impl InlinesParser {
@@ -323,7 +543,23 @@ impl InlinesParser {
         let data = node.data.borrow();
         match &data.value {
             NodeValue::Text(text) => {
-                self.pending_text.push(StyledText::new(text.clone(), style.clone()));
+                let mut text = StyledText::new(text.clone(), style.clone());
+                if let Some(span) = self.spans.last() {
+                    text.style.merge(&TextStyle::default().colors(span.colors.clone()));
+                    text.class = span.class.clone();
+                }
+                self.pending_text.push(text);
+            }
+            NodeValue::HtmlInline(html) => {
+                let html = html.parse().map_err(|e| ParseErrorKind::InvalidHtml(e).with_sourcepos(data.sourcepos))?;
+                match html {
+                    HtmlInline::OpenSpan(span) => self.open_span(span),
+                    HtmlInline::CloseSpan => {
+                        if self.spans.pop().is_none() {
+                            return Err(ParseErrorKind::UnbalancedSpan.with_sourcepos(data.sourcepos));
+                        }
+                    }
+                };
             }
             NodeValue::Code(code) => {
                 self.pending_text.push(StyledText::new(code.literal.clone(), TextStyle::default().code()));
@@ -333,8 +569,15 @@ impl InlinesParser {
             NodeValue::Strikethrough => self.process_children(node, style.clone().strikethrough())?,
             NodeValue::SoftBreak => self.pending_text.push(StyledText::from(" ")),
             NodeValue::Link(link) => {
-                self.pending_text.push(StyledText::new(link.url.clone(), TextStyle::default().link()))
+                let style = style.clone().link(link.url.clone());
+                if node.children().next().is_none() {
+                    // There's no label so the URL itself is the best we can do.
+                    self.pending_text.push(StyledText::new(link.url.clone(), style));
+                } else {
+                    self.process_children(node, style)?;
+                }
             }
+            NodeValue::FootnoteReference(name) => self.pending_text.push(StyledText::footnote_reference(name)),
             NodeValue::LineBreak => {
                 self.store_pending_text();
                 self.inlines.push(Inline::LineBreak);
@@ -351,6 +594,19 @@ impl InlinesParser {
         Ok(())
     }
 
+    fn open_span(&mut self, mut span: Span) {
+        // Nested spans inherit anything they don't override from the ones they're in. A class
+        // overrides any colors set in the spans it's in as those would otherwise take precedence.
+        if let Some(parent) = self.spans.last() {
+            if span.class.is_none() {
+                span.colors.foreground = span.colors.foreground.or(parent.colors.foreground);
+                span.colors.background = span.colors.background.or(parent.colors.background);
+                span.class = parent.class.clone();
+            }
+        }
+        self.spans.push(span);
+    }
+
     fn process_children<'a>(&mut self, node: &'a AstNode<'a>, style: TextStyle) -> ParseResult<()> {
         for node in node.children() {
             self.process_node(node, style.clone())?;
@@ -409,6 +665,18 @@ pub enum ParseErrorKind {
     /// We don't support unfenced code blocks.
     UnfencedCodeBlock,
 
+    /// A code block contains an attribute we don't know about.
+    InvalidCodeAttribute(String),
+
+    /// The lines to be highlighted in a code block are invalid.
+    InvalidHighlightedLines(String),
+
+    /// Inline HTML that we don't know how to handle.
+    InvalidHtml(ParseHtmlError),
+
+    /// A closing `</span>` tag without a matching opening one.
+    UnbalancedSpan,
+
     /// An internal parsing error.
     Internal(String),
 }
@@ -421,6 +689,10 @@ impl Display for ParseErrorKind {
                 write!(f, "unsupported structure in {container}: {element}")
             }
             Self::UnfencedCodeBlock => write!(f, "only fenced code blocks are supported"),
+            Self::InvalidCodeAttribute(attribute) => write!(f, "invalid code attribute: {attribute}"),
+            Self::InvalidHighlightedLines(lines) => write!(f, "invalid highlighted lines: {lines}"),
+            Self::InvalidHtml(error) => write!(f, "invalid html: {error}"),
+            Self::UnbalancedSpan => write!(f, "closing span tag without an opening one"),
             Self::Internal(message) => write!(f, "internal error: {message}"),
         }
     }
@@ -479,6 +751,9 @@ mod test {
     use std::path::Path;
 
     use super::*;
+    use crate::theme::Colors;
+    use crossterm::style::Color;
+    use rstest::rstest;
 
     fn parse_single(input: &str) -> MarkdownElement {
         let arena = Arena::new();
@@ -528,17 +803,88 @@ boop
         assert_eq!(elements, expected_elements);
     }
 
+    #[test]
+    fn colored_spans() {
+        let parsed = parse_single(
+            "<span style=\"color: red\">red **bold**</span> <span class=\"cool\">cool <span style=\"color: blue\">blue</span></span>",
+        );
+        let MarkdownElement::Paragraph(elements) = parsed else { panic!("not a paragraph: {parsed:?}") };
+        let red = TextStyle::default().colors(Colors { foreground: Some(Color::Red), background: None });
+        let blue = TextStyle::default().colors(Colors { foreground: Some(Color::Blue), background: None });
+        let with_class = |mut text: StyledText| {
+            text.class = Some("cool".into());
+            text
+        };
+        let expected_chunks = vec![
+            StyledText::new("red ", red.clone()),
+            StyledText::new("bold", red.bold()),
+            StyledText::from(" "),
+            with_class(StyledText::from("cool ")),
+            with_class(StyledText::new("blue", blue)),
+        ];
+        let expected_elements = &[ParagraphElement::Text(Text { chunks: expected_chunks })];
+        assert_eq!(elements, expected_elements);
+    }
+
+    #[test]
+    fn class_within_colored_span() {
+        let parsed = parse_single("<span style=\"color: red\">red <span class=\"cool\">cool</span></span>");
+        let MarkdownElement::Paragraph(elements) = parsed else { panic!("not a paragraph: {parsed:?}") };
+        let red = TextStyle::default().colors(Colors { foreground: Some(Color::Red), background: None });
+        let mut cool = StyledText::from("cool");
+        cool.class = Some("cool".into());
+        let expected_chunks = vec![StyledText::new("red ", red), cool];
+        let expected_elements = &[ParagraphElement::Text(Text { chunks: expected_chunks })];
+        assert_eq!(elements, expected_elements);
+    }
+
+    #[rstest]
+    #[case::unsupported_tag("<div>hi</div> there")]
+    #[case::unbalanced_span("hi</span>")]
+    fn invalid_inline_html(#[case] input: &str) {
+        let arena = Arena::new();
+        let result = MarkdownParser::new(&arena).parse(input);
+        assert!(result.is_err());
+    }
+
     #[test]
     fn link() {
-        let parsed = parse_single("my [website](https://example.com)");
+        let parsed = parse_single("my [**cool** website](https://example.com) and [](https://empty.com)");
         let MarkdownElement::Paragraph(elements) = parsed else { panic!("not a paragraph: {parsed:?}") };
-        let expected_chunks =
-            vec![StyledText::from("my "), StyledText::new("https://example.com", TextStyle::default().link())];
+        let link = TextStyle::default().link("https://example.com");
+        let expected_chunks = vec![
+            StyledText::from("my "),
+            StyledText::new("cool", link.clone().bold()),
+            StyledText::new(" website", link),
+            StyledText::from(" and "),
+            StyledText::new("https://empty.com", TextStyle::default().link("https://empty.com")),
+        ];
 
         let expected_elements = &[ParagraphElement::Text(Text { chunks: expected_chunks })];
         assert_eq!(elements, expected_elements);
     }
 
+    #[test]
+    fn footnotes() {
+        let parsed = parse_all("hi[^note]\n\n[^note]: a *note*\n\n    more");
+        let [MarkdownElement::Paragraph(elements), MarkdownElement::FootnoteDefinition { name, text }] =
+            parsed.as_slice()
+        else {
+            panic!("unexpected elements: {parsed:?}")
+        };
+        let expected_chunks = vec![StyledText::from("hi"), StyledText::footnote_reference("note")];
+        assert_eq!(elements, &[ParagraphElement::Text(Text { chunks: expected_chunks })]);
+        assert_eq!(name, "note");
+
+        let expected_chunks = vec![
+            StyledText::from("a "),
+            StyledText::new("note", TextStyle::default().italics()),
+            StyledText::from(" "),
+            StyledText::from("more"),
+        ];
+        assert_eq!(text, &Text { chunks: expected_chunks });
+    }
+
     #[test]
     fn image() {
         let parsed = parse_single("![](potato.png)");
@@ -600,6 +946,90 @@ Title
         assert_eq!(next().depth, 0);
     }
 
+    #[rstest]
+    #[case::small("3. three\n4. four", 3)]
+    #[case::large("70000. three\n70001. four", 70000)]
+    fn ordered_list_start(#[case] input: &str, #[case] start: u32) {
+        let parsed = parse_single(input);
+        let MarkdownElement::List(items) = parsed else { panic!("not a list: {parsed:?}") };
+        let item_types: Vec<_> = items.into_iter().map(|item| item.item_type).collect();
+        assert_eq!(item_types, &[ListItemType::OrderedPeriod(start), ListItemType::OrderedPeriod(start + 1)]);
+    }
+
+    #[test]
+    fn list_item_blocks() {
+        let parsed = parse_single(
+            r"
+* One
+
+  more
+  ```bash
+  echo hi
+  ```
+  > quote
+  * Sub
+
+  after
+* Two",
+        );
+        let MarkdownElement::List(items) = parsed else { panic!("not a list: {parsed:?}") };
+        let [one, sub, continuation, two] = items.as_slice() else { panic!("unexpected items: {items:?}") };
+        assert_eq!(one.contents, Text::from("One"));
+        let [MarkdownElement::Paragraph(_), MarkdownElement::Code(_), MarkdownElement::BlockQuote(_)] =
+            one.blocks.as_slice()
+        else {
+            panic!("unexpected blocks: {:?}", one.blocks)
+        };
+        assert_eq!(sub.depth, 1);
+        assert_eq!(continuation.depth, 0);
+        assert_eq!(continuation.item_type, ListItemType::Continuation);
+        assert_eq!(continuation.contents, Text::from("after"));
+        assert_eq!(two.contents, Text::from("Two"));
+        assert!(two.blocks.is_empty());
+
+        let parsed = parse_single("* * nested\n\n  after");
+        let MarkdownElement::List(items) = parsed else { panic!("not a list: {parsed:?}") };
+        let item_types: Vec<_> = items.into_iter().map(|item| (item.depth, item.item_type)).collect();
+        assert_eq!(item_types, &[(1, ListItemType::Unordered), (0, ListItemType::Continuation)]);
+    }
+
+    #[test]
+    fn list_item_images() {
+        let parsed = parse_single("* one ![](a.png) two\n* ![](b.png)");
+        let MarkdownElement::List(items) = parsed else { panic!("not a list: {parsed:?}") };
+        let [first, second] = items.as_slice() else { panic!("unexpected items: {items:?}") };
+        assert_eq!(first.contents, Text::from("one "));
+        let [MarkdownElement::Image(image), MarkdownElement::Paragraph(_)] = first.blocks.as_slice() else {
+            panic!("unexpected blocks: {:?}", first.blocks)
+        };
+        assert_eq!(image, Path::new("a.png"));
+        assert!(second.contents.chunks.is_empty());
+        let [MarkdownElement::Image(image)] = second.blocks.as_slice() else {
+            panic!("unexpected blocks: {:?}", second.blocks)
+        };
+        assert_eq!(image, Path::new("b.png"));
+    }
+
+    #[test]
+    fn task_list() {
+        let parsed = parse_single(
+            r"
+ * [ ] One
+    1. [x] Sub1
+    2. [ ] Sub2
+ * Two",
+        );
+        let MarkdownElement::List(items) = parsed else { panic!("not a list: {parsed:?}") };
+        let item_types: Vec<_> = items.into_iter().map(|item| (item.depth, item.item_type, item.checkbox)).collect();
+        let expected = &[
+            (0, ListItemType::Unordered, Some(false)),
+            (1, ListItemType::OrderedPeriod(1), Some(true)),
+            (1, ListItemType::OrderedPeriod(2), Some(false)),
+            (0, ListItemType::Unordered, None),
+        ];
+        assert_eq!(item_types, expected);
+    }
+
     #[test]
     fn line_breaks() {
         let parsed = parse_all(
@@ -636,6 +1066,72 @@ let q = 42;
         let MarkdownElement::Code(code) = parsed else { panic!("not a code block: {parsed:?}") };
         assert_eq!(code.language, ProgrammingLanguage::Rust);
         assert_eq!(code.contents, "let q = 42;\n");
+        assert!(!code.attributes.execute);
+    }
+
+    #[test]
+    fn executable_code_block() {
+        let parsed = parse_single(
+            r"
+```bash +exec
+echo hi
+```
+",
+        );
+        let MarkdownElement::Code(code) = parsed else { panic!("not a code block: {parsed:?}") };
+        assert_eq!(code.language, ProgrammingLanguage::Bash);
+        assert!(code.attributes.execute);
+    }
+
+    #[test]
+    fn invalid_code_attribute() {
+        let arena = Arena::new();
+        let result = MarkdownParser::new(&arena).parse("```bash +potato\necho hi\n```");
+        let Err(error) = result else { panic!("parsing didn't fail") };
+        assert!(matches!(error.kind, ParseErrorKind::InvalidCodeAttribute(attribute) if attribute == "+potato"));
+    }
+
+    #[rstest]
+    #[case::title("bash title", "bash")]
+    #[case::comma_separated("rust,ignore", "rust")]
+    #[case::mixed("rust,no_run +exec foo=bar", "rust")]
+    fn foreign_code_attributes(#[case] info: &str, #[case] expected: &str) {
+        let (language, _) = MarkdownParser::parse_block_info(info).expect("parse failed");
+        assert_eq!(language, expected);
+    }
+
+    #[test]
+    fn highlighted_lines() {
+        let (language, attributes) =
+            MarkdownParser::parse_block_info("rust {1, 3-5} +exec +line_numbers").expect("parse failed");
+        assert_eq!(language, "rust");
+        assert!(attributes.execute);
+        assert!(attributes.line_numbers);
+        let expected = vec![HighlightGroup::new(vec![Highlight::Single(1), Highlight::Range(3..=5)])];
+        assert_eq!(attributes.highlight_groups, expected);
+    }
+
+    #[test]
+    fn highlight_groups() {
+        let (_, attributes) = MarkdownParser::parse_block_info("rust {1|2-3|all}").expect("parse failed");
+        let expected = vec![
+            HighlightGroup::new(vec![Highlight::Single(1)]),
+            HighlightGroup::new(vec![Highlight::Range(2..=3)]),
+            HighlightGroup::new(vec![Highlight::All]),
+        ];
+        assert_eq!(attributes.highlight_groups, expected);
+    }
+
+    #[rstest]
+    #[case::unterminated("rust {1,2")]
+    #[case::zero("rust {0}")]
+    #[case::inverted_range("rust {3-1}")]
+    #[case::not_a_number("rust {a}")]
+    #[case::empty_group("rust {1||2}")]
+    #[case::duplicate("rust {1} {2}")]
+    fn invalid_highlighted_lines(#[case] info: &str) {
+        let result = MarkdownParser::parse_block_info(info);
+        assert!(matches!(result, Err(ParseErrorKind::InvalidHighlightedLines(_))), "{result:?}");
     }
 
     #[test]
@@ -659,13 +1155,21 @@ let q = 42;
 | Carrot | Yuck |
 ",
         );
-        let MarkdownElement::Table(Table { header, rows }) = parsed else { panic!("not a table: {parsed:?}") };
+        let MarkdownElement::Table(Table { header, rows, .. }) = parsed else { panic!("not a table: {parsed:?}") };
         assert_eq!(header.0.len(), 2);
         assert_eq!(rows.len(), 2);
         assert_eq!(rows[0].0.len(), 2);
         assert_eq!(rows[1].0.len(), 2);
     }
 
+    #[test]
+    fn table_alignments() {
+        let parsed = parse_single("| a | b | c | d |\n|---|:--|:-:|--:|\n| 1 | 2 | 3 | 4 |");
+        let MarkdownElement::Table(table) = parsed else { panic!("not a table: {parsed:?}") };
+        let expected = &[ColumnAlignment::Left, ColumnAlignment::Left, ColumnAlignment::Center, ColumnAlignment::Right];
+        assert_eq!(table.alignments, expected);
+    }
+
     #[test]
     fn comment() {
         let parsed = parse_single(
@@ -702,13 +1206,63 @@ let q = 42;
 > * b
 ",
         );
-        let MarkdownElement::BlockQuote(lines) = parsed else { panic!("not a block quote: {parsed:?}") };
-        assert_eq!(lines.len(), 5);
-        assert_eq!(lines[0], "bar");
-        assert_eq!(lines[1], "foo");
-        assert_eq!(lines[2], "");
-        assert_eq!(lines[3], "* a");
-        assert_eq!(lines[4], "* b");
+        let MarkdownElement::BlockQuote(elements) = parsed else { panic!("not a block quote: {parsed:?}") };
+        let [MarkdownElement::Paragraph(paragraph), MarkdownElement::List(items)] = elements.as_slice() else {
+            panic!("unexpected quote contents: {elements:?}")
+        };
+        assert_eq!(paragraph.len(), 1);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].contents, Text::from("a"));
+        assert_eq!(items[1].contents, Text::from("b"));
+    }
+
+    #[test]
+    fn block_quote_formatting() {
+        let parsed = parse_single(
+            r"
+> **bold** and `code`
+>
+> > nested
+",
+        );
+        let MarkdownElement::BlockQuote(elements) = parsed else { panic!("not a block quote: {parsed:?}") };
+        let [MarkdownElement::Paragraph(paragraph), MarkdownElement::BlockQuote(nested)] = elements.as_slice() else {
+            panic!("unexpected quote contents: {elements:?}")
+        };
+        let expected = vec![
+            StyledText::new("bold", TextStyle::default().bold()),
+            StyledText::from(" and "),
+            StyledText::new("code", TextStyle::default().code()),
+        ];
+        assert_eq!(paragraph, &[ParagraphElement::Text(Text { chunks: expected })]);
+        assert_eq!(nested.len(), 1);
+    }
+
+    #[rstest]
+    #[case::note("> [!NOTE]\n> hi", AlertType::Note, None)]
+    #[case::lowercase("> [!tip]\n> hi", AlertType::Tip, None)]
+    #[case::title("> [!WARNING] Be careful\n> hi", AlertType::Warning, Some("Be careful"))]
+    #[case::separate_paragraph("> [!CAUTION]\n>\n> hi", AlertType::Caution, None)]
+    fn alert(#[case] input: &str, #[case] expected_type: AlertType, #[case] expected_title: Option<&str>) {
+        let parsed = parse_single(input);
+        let MarkdownElement::Alert { alert_type, title, elements } = parsed else { panic!("not an alert: {parsed:?}") };
+        assert_eq!(alert_type, expected_type);
+        assert_eq!(title.as_deref(), expected_title);
+        let expected = MarkdownElement::Paragraph(vec![ParagraphElement::Text(Text::from("hi"))]);
+        assert_eq!(elements, &[expected]);
+    }
+
+    #[test]
+    fn unknown_alert_type() {
+        let parsed = parse_single("> [!POTATO]\n> hi");
+        assert!(matches!(parsed, MarkdownElement::BlockQuote(_)), "{parsed:?}");
+    }
+
+    #[test]
+    fn block_quote_image() {
+        let arena = Arena::new();
+        let result = MarkdownParser::new(&arena).parse("> ![](potato.png)");
+        assert!(result.is_err());
     }
 
     #[test]
@@ -1,21 +1,40 @@
 use crate::{
-    markdown::text::WeightedLine,
+    markdown::{elements::StyledText, text::WeightedLine},
     render::{media::Image, properties::WindowSize},
     theme::{Alignment, Colors, PresentationTheme},
 };
 use serde::Deserialize;
-use std::rc::Rc;
+use std::{
+    cell::Cell,
+    collections::BTreeMap,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
 /// A presentation.
 pub struct Presentation {
     slides: Vec<Slide>,
     current_slide_index: usize,
+    clock: PresentationClock,
 }
 
 impl Presentation {
     /// Construct a new presentation.
     pub fn new(slides: Vec<Slide>) -> Self {
-        Self { slides, current_slide_index: 0 }
+        Self { slides, current_slide_index: 0, clock: Default::default() }
+    }
+
+    /// Use the given clock to keep track of when this presentation started.
+    pub fn with_clock(mut self, clock: PresentationClock) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    /// Set the time this presentation started at.
+    ///
+    /// Anything in it that displays the time uses this from then on.
+    pub fn start_clock(&self, started_at: Instant) {
+        self.clock.start(started_at);
     }
 
     /// Iterate the slides in this presentation.
@@ -79,6 +98,37 @@ impl Presentation {
         }
     }
 
+    /// Start rendering all on demand operations in the current slide.
+    ///
+    /// Returns whether any of them was started.
+    pub fn start_on_demand_renders(&self) -> bool {
+        let mut started = false;
+        for operation in self.on_demand_operations() {
+            started = operation.start_render() || started;
+        }
+        started
+    }
+
+    /// Checks whether any of the on demand operations in the current slide is still rendering.
+    pub fn is_rendering_on_demand(&self) -> bool {
+        self.on_demand_operations().any(|operation| operation.poll_state() == RenderOnDemandState::Rendering)
+    }
+
+    /// Checks whether the current slide displays anything that changes over time, like a clock.
+    pub fn is_time_dependent(&self) -> bool {
+        self.current_slide().render_operations.iter().any(|operation| match operation {
+            RenderOperation::RenderDynamic(generator) => generator.is_time_dependent(),
+            _ => false,
+        })
+    }
+
+    fn on_demand_operations(&self) -> impl Iterator<Item = &Rc<dyn RenderOnDemand>> {
+        self.current_slide().render_operations.iter().filter_map(|operation| match operation {
+            RenderOperation::RenderOnDemand(operation) => Some(operation),
+            _ => None,
+        })
+    }
+
     /// Jump to a specific slide.
     pub fn jump_slide(&mut self, slide_index: usize) -> bool {
         if slide_index < self.slides.len() {
@@ -90,6 +140,25 @@ impl Presentation {
     }
 }
 
+/// Keeps track of the time a presentation started at.
+///
+/// Clones share the same start time so anything in a presentation that displays the time can hold
+/// one while whoever presents it decides when it started.
+#[derive(Clone, Debug, Default)]
+pub struct PresentationClock(Rc<Cell<Option<Instant>>>);
+
+impl PresentationClock {
+    /// Set the time the presentation started at.
+    pub fn start(&self, started_at: Instant) {
+        self.0.set(Some(started_at));
+    }
+
+    /// The time elapsed since the presentation started, which is zero if it hasn't.
+    pub fn elapsed(&self) -> Duration {
+        self.0.get().map(|started_at| started_at.elapsed()).unwrap_or_default()
+    }
+}
+
 /// A slide.
 ///
 /// Slides are composed of render operations that can be carried out to materialize this slide into
@@ -97,6 +166,14 @@ impl Presentation {
 #[derive(Clone, Debug)]
 pub struct Slide {
     pub render_operations: Vec<RenderOperation>,
+
+    /// The speaker notes for this slide.
+    ///
+    /// These are never rendered as part of the slide itself.
+    pub notes: Vec<String>,
+
+    /// Whether this slide only continues the previous one after a pause.
+    pub after_pause: bool,
 }
 
 /// The metadata for a presentation.
@@ -113,9 +190,41 @@ pub struct PresentationMetadata {
     #[serde(default)]
     pub author: Option<String>,
 
+    /// The date the presentation takes place in.
+    ///
+    /// This can be referenced as `{{date}}` in the presentation's text and footer.
+    #[serde(default)]
+    pub date: Option<String>,
+
+    /// The event the presentation takes place in.
+    ///
+    /// This can be referenced as `{{event}}` in the presentation's text and footer.
+    #[serde(default)]
+    pub event: Option<String>,
+
+    /// Variables that can be referenced as `{{name}}` in the presentation's text and footer.
+    #[serde(default)]
+    pub vars: BTreeMap<String, String>,
+
+    /// The amount of time the presentation is expected to take, like `20m` or `1h30m`.
+    #[serde(default)]
+    pub duration: Option<String>,
+
     /// The presentation's theme metadata.
     #[serde(default)]
     pub theme: PresentationThemeMetadata,
+
+    /// The presentation's options.
+    #[serde(default)]
+    pub options: PresentationOptions,
+}
+
+/// Options that change how a presentation is laid out.
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct PresentationOptions {
+    /// Whether every slide's contents should be vertically centered.
+    #[serde(default)]
+    pub jump_to_middle: bool,
 }
 
 /// A presentation's theme metadata.
@@ -137,7 +246,7 @@ pub struct PresentationThemeMetadata {
 /// A line of preformatted text to be rendered.
 #[derive(Clone, Debug, PartialEq)]
 pub struct PreformattedLine {
-    pub text: String,
+    pub text: Vec<StyledText>,
     pub unformatted_length: usize,
     pub block_length: usize,
     pub alignment: Alignment,
@@ -158,6 +267,21 @@ pub enum RenderOperation {
     /// Jump the draw cursor into the vertical center, that is, at `screen_height / 2`.
     JumpToVerticalCenter,
 
+    /// Jump the draw cursor to the row that makes everything after it be vertically centered in the
+    /// slide.
+    ///
+    /// Only the operations up to the next one that moves the cursor to an explicit position are
+    /// taken into account.
+    JumpToMiddle,
+
+    /// Jump the draw cursor to the row that makes everything after it end at the bottom of the
+    /// slide.
+    ///
+    /// If the cursor is already past that row it stays where it is, so nothing is drawn on top of
+    /// the contents before it. Like [RenderOperation::JumpToMiddle], this only takes into account
+    /// the operations up to the next one that moves the cursor to an explicit position.
+    JumpToBottom,
+
     /// Jump the draw cursor into the last row in the screen.
     JumpToWindowBottom,
 
@@ -167,6 +291,21 @@ pub enum RenderOperation {
     /// [RenderOperation::JumpToWindowBottom].
     JumpToSlideBottom,
 
+    /// Initialize a column layout.
+    ///
+    /// Every column takes up a portion of the slide's width proportional to its weight, so `[2, 1]`
+    /// means the first column is twice as wide as the second one.
+    InitColumnLayout { columns: Vec<u8> },
+
+    /// Enter a column in the current column layout.
+    ///
+    /// Every operation after this one is rendered within that column until another column is entered
+    /// or the layout is exited.
+    EnterColumn { column: usize },
+
+    /// Exit the current layout and go back to using the slide's entire width.
+    ExitLayout,
+
     /// Render a line of text.
     RenderTextLine { line: WeightedLine, alignment: Alignment },
 
@@ -181,8 +320,8 @@ pub enum RenderOperation {
 
     /// Render a preformatted line.
     ///
-    /// The line will usually already have terminal escape codes that include colors and formatting
-    /// embedded in it.
+    /// The line is made up of pieces of text that already have their colors and formatting set,
+    /// e.g. because they were highlighted.
     RenderPreformattedLine(PreformattedLine),
 
     /// Render a dynamically generated sequence of render operations.
@@ -191,10 +330,43 @@ pub enum RenderOperation {
     /// screen, like window size, without coupling the transformation of markdown into
     /// [RenderOperation] with the screen itself.
     RenderDynamic(Rc<dyn AsRenderOperations>),
+
+    /// Render a sequence of render operations that is only generated on demand.
+    ///
+    /// This is like [RenderOperation::RenderDynamic] except the operations are not generated until
+    /// the user explicitly asks for it, e.g. when executing a piece of code.
+    RenderOnDemand(Rc<dyn RenderOnDemand>),
 }
 
 /// A type that can generate render operations.
 pub trait AsRenderOperations: std::fmt::Debug {
     /// Generate render operations.
     fn as_render_operations(&self, dimensions: &WindowSize) -> Vec<RenderOperation>;
+
+    /// Whether the generated operations depend on the current time.
+    ///
+    /// If they do, they will be periodically generated again while they're being displayed.
+    fn is_time_dependent(&self) -> bool {
+        false
+    }
+}
+
+/// A type that generates render operations on demand.
+pub trait RenderOnDemand: AsRenderOperations {
+    /// Start the rendering process.
+    ///
+    /// Returns whether rendering was started, which won't happen if it was already started before.
+    fn start_render(&self) -> bool;
+
+    /// Get the current state of the rendering process.
+    fn poll_state(&self) -> RenderOnDemandState;
+}
+
+/// The state of a [RenderOnDemand].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum RenderOnDemandState {
+    #[default]
+    NotStarted,
+    Rendering,
+    Rendered,
 }
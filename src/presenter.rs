@@ -0,0 +1,283 @@
+use crate::{
+    export::{style_runs, SlideRenderer},
+    input::{
+        fs::PresentationFileWatcher,
+        user::{UserCommand, UserInput},
+    },
+    loader::PresentationLoader,
+    markdown::{
+        elements::StyledText,
+        text::{WeightedLine, WeightedText},
+    },
+    presentation::{Presentation, RenderOperation},
+    render::{
+        draw::{RenderError, RenderResult, TerminalDrawer},
+        grid::{CellStyle, TerminalGrid, WIDE_CHARACTER_CONTINUATION},
+        properties::WindowSize,
+    },
+    slideshow::{SlideShowError, SlideShowMode},
+    style::TextStyle,
+    sync::{SlideSubscriber, SubscriberEvent},
+    theme::Alignment,
+};
+use std::{
+    io::{self, Stdout},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+const POLL_TIMEOUT: Duration = Duration::from_millis(250);
+
+// The rows taken by the separator and title above the next slide's preview.
+const PREVIEW_HEADER_ROWS: u16 = 5;
+
+/// A presenter view.
+///
+/// This follows a presentation being run by another process and displays the current slide's
+/// speaker notes, a preview of the next slide, and the time elapsed since the presentation was started.
+pub struct PresenterView<'a> {
+    loader: PresentationLoader<'a>,
+    subscriber: SlideSubscriber,
+    mode: SlideShowMode,
+    user_input: UserInput,
+}
+
+impl<'a> PresenterView<'a> {
+    /// Construct a new presenter view.
+    pub fn new(loader: PresentationLoader<'a>, subscriber: SlideSubscriber, mode: SlideShowMode) -> Self {
+        Self { loader, subscriber, mode, user_input: UserInput::default() }
+    }
+
+    /// Run the presenter view until either the user or the presentation exits.
+    pub fn present(mut self, path: &Path) -> Result<(), SlideShowError> {
+        let (mut presentation, included_paths) = self.loader.load_with_includes(path)?;
+        let mut watcher = PresentationFileWatcher::new(path);
+        watcher.watch(included_paths);
+        let mut drawer = TerminalDrawer::new(io::stdout())?;
+        // This is replaced by the time the presentation started at as soon as we hear from it.
+        let mut started_at = Instant::now();
+        let mut needs_redraw = true;
+        let mut drawn_seconds = 0;
+        loop {
+            let elapsed = started_at.elapsed();
+            if needs_redraw || elapsed.as_secs() != drawn_seconds {
+                Self::render(&mut drawer, &presentation, elapsed)?;
+                drawn_seconds = elapsed.as_secs();
+                needs_redraw = false;
+            }
+
+            match self.subscriber.poll_next_event(POLL_TIMEOUT)? {
+                Some(SubscriberEvent::SlideChanged { slide, elapsed }) => {
+                    started_at = Instant::now().checked_sub(elapsed).unwrap_or(started_at);
+                    presentation.start_clock(started_at);
+                    needs_redraw |= presentation.jump_slide(slide);
+                }
+                Some(SubscriberEvent::Disconnected) => return Ok(()),
+                None => (),
+            };
+            match self.user_input.poll_next_command(Duration::ZERO)? {
+                Some(UserCommand::Exit) => return Ok(()),
+                Some(UserCommand::Redraw) => needs_redraw = true,
+                _ => (),
+            };
+            if matches!(self.mode, SlideShowMode::Development) && watcher.has_modifications()? {
+                // The presentation itself will display any errors so we simply keep the last good
+                // version around.
+                if let Ok((mut reloaded, included_paths)) = self.loader.load_with_includes(path) {
+                    watcher.watch(included_paths);
+                    reloaded.jump_slide(presentation.current_slide_index());
+                    reloaded.start_clock(started_at);
+                    presentation = reloaded;
+                    needs_redraw = true;
+                }
+            }
+        }
+    }
+
+    fn render(drawer: &mut TerminalDrawer<Stdout>, presentation: &Presentation, elapsed: Duration) -> RenderResult {
+        let dimensions = WindowSize::current()?;
+        let operations = Self::build_operations(presentation, elapsed, &dimensions);
+        let result = drawer.render_operations(&operations);
+        // Same as in the presentation itself, wait for the user to resize the screen.
+        if matches!(result, Err(RenderError::TerminalTooSmall)) { Ok(()) } else { result }
+    }
+
+    fn build_operations(
+        presentation: &Presentation,
+        elapsed: Duration,
+        dimensions: &WindowSize,
+    ) -> Vec<RenderOperation> {
+        let current_index = presentation.current_slide_index();
+        let total_slides = presentation.iter_slides().count();
+        let elapsed = elapsed.as_secs();
+        let elapsed = format!("{:02}:{:02}:{:02}", elapsed / 3600, (elapsed / 60) % 60, elapsed % 60);
+        let mut operations = vec![
+            RenderOperation::ClearScreen,
+            RenderOperation::RenderLineBreak,
+            Self::text_line(format!("Slide {} / {total_slides}", current_index + 1), TextStyle::default().bold()),
+            RenderOperation::RenderTextLine {
+                line: WeightedLine::from(vec![WeightedText::from(StyledText::from(elapsed))]),
+                alignment: Alignment::Right { margin: 2 },
+            },
+            RenderOperation::RenderLineBreak,
+            RenderOperation::RenderLineBreak,
+            Self::text_line("Speaker notes", TextStyle::default().bold()),
+            RenderOperation::RenderLineBreak,
+            RenderOperation::RenderLineBreak,
+        ];
+
+        let notes = &presentation.current_slide().notes;
+        if notes.is_empty() {
+            operations.push(Self::text_line("This slide has no notes", TextStyle::default().italics()));
+            operations.push(RenderOperation::RenderLineBreak);
+        }
+        for line in notes.iter().flat_map(|note| note.lines()) {
+            operations.push(Self::text_line(line, TextStyle::default()));
+            operations.push(RenderOperation::RenderLineBreak);
+        }
+
+        // The next slide is previewed in the bottom half of the screen.
+        let preview_dimensions = Self::preview_dimensions(dimensions);
+        operations.extend([
+            RenderOperation::JumpToBottom,
+            RenderOperation::RenderSeparator,
+            RenderOperation::RenderLineBreak,
+            RenderOperation::RenderLineBreak,
+            Self::text_line("Next slide", TextStyle::default().bold()),
+            RenderOperation::RenderLineBreak,
+            RenderOperation::RenderLineBreak,
+        ]);
+        // Slides that only continue the current one after a pause look almost the same so skip them.
+        let next_slide =
+            presentation.iter_slides().enumerate().skip(current_index + 1).find(|(_, slide)| !slide.after_pause);
+        let preview = match next_slide {
+            Some((_, slide)) => SlideRenderer::new(preview_dimensions).render(slide),
+            None => {
+                operations.push(Self::text_line("This is the last slide", TextStyle::default().italics()));
+                return operations;
+            }
+        };
+        match preview {
+            Ok(grid) => {
+                for line in Self::grid_lines(&grid) {
+                    let alignment = Alignment::Left { margin: 2 };
+                    operations.push(RenderOperation::RenderTextLine { line, alignment });
+                    operations.push(RenderOperation::RenderLineBreak);
+                }
+            }
+            Err(e) => operations.push(Self::text_line(
+                format!("The next slide can't be previewed: {e}"),
+                TextStyle::default().italics(),
+            )),
+        };
+        operations
+    }
+
+    fn preview_dimensions(dimensions: &WindowSize) -> WindowSize {
+        let rows = (dimensions.rows / 2).saturating_sub(PREVIEW_HEADER_ROWS);
+        let columns = dimensions.columns.saturating_sub(4);
+        // Keep the same pixels per cell as the actual window so images are laid out the same way.
+        let scale = |pixels: u16, cells: u16, total: u16| (pixels as u32 * cells as u32 / total.max(1) as u32) as u16;
+        WindowSize {
+            rows,
+            columns,
+            height: scale(dimensions.height, rows, dimensions.rows),
+            width: scale(dimensions.width, columns, dimensions.columns),
+        }
+    }
+
+    fn grid_lines(grid: &TerminalGrid) -> Vec<WeightedLine> {
+        let mut lines = Vec::new();
+        for row in grid.rows() {
+            let mut texts = Vec::new();
+            for (start, length, cell) in style_runs(row, |a, b| a.style == b.style) {
+                let text: String = row[start..start + length]
+                    .iter()
+                    .map(|cell| cell.character)
+                    .filter(|c| *c != WIDE_CHARACTER_CONTINUATION)
+                    .collect();
+                texts.push(WeightedText::from(StyledText::new(text, Self::text_style(&cell.style))));
+            }
+            lines.push(WeightedLine::from(texts));
+        }
+        lines
+    }
+
+    fn text_style(style: &CellStyle) -> TextStyle {
+        let mut text_style = TextStyle::default().colors(style.colors.clone());
+        if style.bold {
+            text_style = text_style.bold();
+        }
+        if style.italics {
+            text_style = text_style.italics();
+        }
+        if style.strikethrough {
+            text_style = text_style.strikethrough();
+        }
+        if let Some(url) = &style.url {
+            text_style = text_style.link(url.clone());
+        }
+        text_style
+    }
+
+    fn text_line<S: Into<String>>(text: S, style: TextStyle) -> RenderOperation {
+        let text = WeightedText::from(StyledText::new(text, style));
+        RenderOperation::RenderTextLine {
+            line: WeightedLine::from(vec![text]),
+            alignment: Alignment::Left { margin: 2 },
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::presentation::Slide;
+
+    fn text_slide(lines: &[&str], after_pause: bool) -> Slide {
+        let mut render_operations = vec![RenderOperation::ClearScreen];
+        for line in lines {
+            render_operations.push(PresenterView::text_line(*line, TextStyle::default()));
+            render_operations.push(RenderOperation::RenderLineBreak);
+        }
+        Slide { render_operations, notes: Vec::new(), after_pause }
+    }
+
+    fn preview_text(operations: &[RenderOperation]) -> Vec<String> {
+        let header = operations
+            .iter()
+            .position(|operation| matches!(operation, RenderOperation::RenderSeparator))
+            .expect("no separator");
+        operations[header..]
+            .iter()
+            .filter_map(|operation| match operation {
+                RenderOperation::RenderTextLine { line, .. } => {
+                    let text: String = line.iter_texts().map(|text| text.text.text.as_str()).collect();
+                    Some(text.trim().to_string())
+                }
+                _ => None,
+            })
+            .filter(|text| !text.is_empty())
+            .collect()
+    }
+
+    #[test]
+    fn preview_skips_pauses() {
+        let presentation = Presentation::new(vec![
+            text_slide(&["hi"], false),
+            text_slide(&["hi", "there"], true),
+            text_slide(&["bye"], false),
+        ]);
+        let dimensions = WindowSize { rows: 30, columns: 20, width: 200, height: 600 };
+        let operations = PresenterView::build_operations(&presentation, Duration::ZERO, &dimensions);
+        assert_eq!(preview_text(&operations), &["Next slide", "bye"]);
+    }
+
+    #[test]
+    fn last_slide() {
+        let presentation = Presentation::new(vec![text_slide(&["hi"], false), text_slide(&["hi", "there"], true)]);
+        let dimensions = WindowSize { rows: 30, columns: 20, width: 200, height: 600 };
+        let operations = PresenterView::build_operations(&presentation, Duration::ZERO, &dimensions);
+        assert_eq!(preview_text(&operations), &["Next slide", "This is the last slide"]);
+    }
+}
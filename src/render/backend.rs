@@ -0,0 +1,163 @@
+use crate::{
+    render::{
+        media::{Image, MediaRender, RenderImageError},
+        properties::{WindowRect, WindowSize},
+    },
+    style::TextStyle,
+    theme::Colors,
+};
+use crossterm::{
+    cursor, style,
+    terminal::{self, ClearType},
+    QueueableCommand,
+};
+use std::io;
+
+/// A backend that presentations can be drawn into.
+///
+/// This abstracts away the primitives used while drawing so that presentations can be rendered
+/// into something other than a terminal.
+pub trait RenderBackend {
+    /// Get the size of the window being drawn into.
+    fn window_size(&mut self) -> io::Result<WindowSize>;
+
+    /// Get the row the cursor is currently at.
+    fn cursor_row(&mut self) -> io::Result<u16>;
+
+    /// Move the cursor to the given position.
+    fn move_to(&mut self, column: u16, row: u16) -> io::Result<()>;
+
+    /// Move the cursor to the given column, keeping it in the same row.
+    fn move_to_column(&mut self, column: u16) -> io::Result<()>;
+
+    /// Move the cursor to the given row, keeping it in the same column.
+    fn move_to_row(&mut self, row: u16) -> io::Result<()>;
+
+    /// Move the cursor down by the given number of rows.
+    fn move_down(&mut self, amount: u16) -> io::Result<()>;
+
+    /// Move the cursor to the beginning of the line that's the given number of rows below.
+    fn move_to_next_line(&mut self, amount: u16) -> io::Result<()>;
+
+    /// Clear the entire window using the current background color.
+    fn clear_screen(&mut self) -> io::Result<()>;
+
+    /// Set the colors to be used for any subsequent text.
+    fn set_colors(&mut self, colors: &Colors) -> io::Result<()>;
+
+    /// Print a piece of text using the given style.
+    ///
+    /// Any colors defined in the style take precedence over the current ones.
+    fn print_text(&mut self, text: &str, style: &TextStyle) -> io::Result<()>;
+
+    /// Draw an image in the current row, centered within the given rectangle.
+    fn draw_image(&mut self, image: &Image, rect: &WindowRect) -> Result<(), RenderImageError>;
+
+    /// Flush any pending operations.
+    fn flush(&mut self) -> io::Result<()>;
+}
+
+/// A backend that draws into a terminal using crossterm.
+pub struct TerminalBackend<W: io::Write> {
+    handle: W,
+}
+
+impl<W: io::Write> TerminalBackend<W> {
+    /// Construct a new backend that writes into the given handle.
+    pub fn new(handle: W) -> Self {
+        Self { handle }
+    }
+
+    /// Get a mutable reference to the underlying handle.
+    pub fn handle(&mut self) -> &mut W {
+        &mut self.handle
+    }
+}
+
+impl<W: io::Write> RenderBackend for TerminalBackend<W> {
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        WindowSize::current()
+    }
+
+    fn cursor_row(&mut self) -> io::Result<u16> {
+        // The terminal can only tell us where the cursor is once everything before it is there.
+        self.handle.flush()?;
+        Ok(cursor::position()?.1)
+    }
+
+    fn move_to(&mut self, column: u16, row: u16) -> io::Result<()> {
+        self.handle.queue(cursor::MoveTo(column, row))?;
+        Ok(())
+    }
+
+    fn move_to_column(&mut self, column: u16) -> io::Result<()> {
+        self.handle.queue(cursor::MoveToColumn(column))?;
+        Ok(())
+    }
+
+    fn move_to_row(&mut self, row: u16) -> io::Result<()> {
+        self.handle.queue(cursor::MoveToRow(row))?;
+        Ok(())
+    }
+
+    fn move_down(&mut self, amount: u16) -> io::Result<()> {
+        self.handle.queue(cursor::MoveDown(amount))?;
+        Ok(())
+    }
+
+    fn move_to_next_line(&mut self, amount: u16) -> io::Result<()> {
+        self.handle.queue(cursor::MoveToNextLine(amount))?;
+        Ok(())
+    }
+
+    fn clear_screen(&mut self) -> io::Result<()> {
+        self.handle.queue(terminal::Clear(ClearType::All))?;
+        Ok(())
+    }
+
+    fn set_colors(&mut self, colors: &Colors) -> io::Result<()> {
+        self.handle
+            .queue(style::SetColors(style::Colors { background: colors.background, foreground: colors.foreground }))?;
+        Ok(())
+    }
+
+    fn print_text(&mut self, text: &str, style: &TextStyle) -> io::Result<()> {
+        match &style.url {
+            // Links are wrapped in an OSC 8 sequence so terminals make them clickable.
+            Some(url) => {
+                self.handle.queue(style::Print(format!("\x1b]8;;{url}\x1b\\")))?;
+                self.handle.queue(style::PrintStyledContent(style.apply(text)))?;
+                self.handle.queue(style::Print("\x1b]8;;\x1b\\"))?;
+            }
+            None => {
+                self.handle.queue(style::PrintStyledContent(style.apply(text)))?;
+            }
+        };
+        Ok(())
+    }
+
+    fn draw_image(&mut self, image: &Image, rect: &WindowRect) -> Result<(), RenderImageError> {
+        // Images are printed straight into the terminal so make sure everything before it is there.
+        self.handle.flush()?;
+        MediaRender.draw_image(image, rect)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.handle.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn hyperlinks() {
+        let mut backend = TerminalBackend::new(Vec::new());
+        backend.print_text("site", &TextStyle::default().link("https://example.com")).unwrap();
+        let output = String::from_utf8(backend.handle().clone()).unwrap();
+        assert!(output.starts_with("\x1b]8;;https://example.com\x1b\\"), "{output:?}");
+        assert!(output.ends_with("\x1b]8;;\x1b\\"), "{output:?}");
+        assert!(output.contains("site"), "{output:?}");
+    }
+}
@@ -1,11 +1,13 @@
-use super::operator::RenderOperator;
+use super::{
+    backend::{RenderBackend, TerminalBackend},
+    operator::RenderOperator,
+};
 use crate::{
     markdown::{
         elements::StyledText,
         text::{WeightedLine, WeightedText},
     },
-    presentation::{Presentation, RenderOperation},
-    render::properties::WindowSize,
+    presentation::{Presentation, RenderOperation, Slide},
     style::TextStyle,
     theme::{Alignment, Colors},
 };
@@ -17,12 +19,15 @@ use crossterm::{
 };
 use std::io;
 
+/// The number of rows at the bottom of the window that are reserved for the footer.
+const FOOTER_ROWS: u16 = 3;
+
 /// The result of a render operation.
 pub type RenderResult = Result<(), RenderError>;
 
 /// Allows drawing elements in the terminal.
 pub struct TerminalDrawer<W: io::Write> {
-    handle: W,
+    backend: TerminalBackend<W>,
 }
 
 impl<W> TerminalDrawer<W>
@@ -34,26 +39,36 @@ where
         enable_raw_mode()?;
         handle.queue(cursor::Hide)?;
         handle.queue(terminal::EnterAlternateScreen)?;
-        Ok(Self { handle })
+        Ok(Self { backend: TerminalBackend::new(handle) })
     }
 
-    /// Render a slide.
-    pub fn render_slide(&mut self, presentation: &Presentation) -> RenderResult {
-        let window_dimensions = WindowSize::current()?;
-        let slide_dimensions = window_dimensions.shrink_rows(3);
-        let slide = presentation.current_slide();
-        let mut operator =
-            RenderOperator::new(&mut self.handle, slide_dimensions, window_dimensions, Default::default());
-        for element in &slide.render_operations {
-            operator.render(element)?;
-        }
-        self.handle.flush()?;
+    /// Render a slide, scrolled down by the given number of rows.
+    pub fn render_slide(
+        &mut self,
+        presentation: &Presentation,
+        scroll_offset: u16,
+    ) -> Result<SlideOverflow, RenderError> {
+        render_slide(&mut self.backend, presentation.current_slide(), scroll_offset)
+    }
+
+    /// Render the parts of the current slide that change over time again, e.g. a footer that
+    /// displays the time.
+    pub fn render_time_dependent(&mut self, presentation: &Presentation) -> RenderResult {
+        render_time_dependent(&mut self.backend, presentation.current_slide())
+    }
+
+    /// Render a warning right below the slide.
+    pub fn render_warning(&mut self, message: &str) -> RenderResult {
+        let dimensions = self.backend.window_size()?;
+        let style = TextStyle::default().colors(Colors { foreground: Some(Color::Red), background: None });
+        self.backend.move_to(1, dimensions.rows.saturating_sub(FOOTER_ROWS))?;
+        self.backend.print_text(message, &style)?;
+        self.backend.flush()?;
         Ok(())
     }
 
     /// Render an error.
     pub fn render_error(&mut self, message: &str) -> RenderResult {
-        let dimensions = WindowSize::current()?;
         let heading = vec![
             WeightedText::from(StyledText::new("Error loading presentation", TextStyle::default().bold())),
             WeightedText::from(StyledText::from(": ")),
@@ -69,22 +84,86 @@ where
             RenderOperation::RenderLineBreak,
             RenderOperation::RenderTextLine { line: WeightedLine::from(error), alignment: alignment.clone() },
         ];
-        let mut operator = RenderOperator::new(&mut self.handle, dimensions.clone(), dimensions, Default::default());
-        for operation in operations {
-            operator.render(&operation)?;
-        }
-        self.handle.flush()?;
+        self.render_operations(&operations)
+    }
+
+    /// Render a sequence of operations that use the entire window.
+    pub fn render_operations(&mut self, operations: &[RenderOperation]) -> RenderResult {
+        let dimensions = self.backend.window_size()?;
+        let mut operator = RenderOperator::new(&mut self.backend, dimensions.clone(), dimensions, Default::default());
+        operator.render(operations)?;
+        self.backend.flush()?;
         Ok(())
     }
 }
 
+/// Render a slide into the given backend, scrolled down by the given number of rows.
+///
+/// The bottom rows of the window are reserved for the slide's footer. If the slide doesn't fit in
+/// the rows left, an indicator that there's more content above and/or below is displayed.
+pub fn render_slide<B: RenderBackend>(
+    backend: &mut B,
+    slide: &Slide,
+    scroll_offset: u16,
+) -> Result<SlideOverflow, RenderError> {
+    let window_dimensions = backend.window_size()?;
+    let slide_dimensions = window_dimensions.shrink_rows(FOOTER_ROWS);
+    let mut operator = RenderOperator::new(backend, slide_dimensions.clone(), window_dimensions, Default::default())
+        .with_scroll_offset(scroll_offset);
+    operator.render(&slide.render_operations)?;
+
+    let overflow = SlideOverflow { rows: operator.content_height().saturating_sub(slide_dimensions.rows) };
+    if overflow.rows > 0 {
+        let above = if scroll_offset > 0 { "▲" } else { " " };
+        let below = if scroll_offset < overflow.rows { "▼" } else { " " };
+        backend.move_to(slide_dimensions.columns.saturating_sub(3), slide_dimensions.rows)?;
+        backend.print_text(&format!("{above}{below}"), &TextStyle::default())?;
+    }
+    backend.flush()?;
+    Ok(overflow)
+}
+
+/// Render the operations in a slide that depend on the current time on top of what's already drawn.
+///
+/// Everything else in the slide is left as is. This relies on those operations drawing into fixed
+/// positions, like footers do, and on the text they draw not shrinking over time.
+pub fn render_time_dependent<B: RenderBackend>(backend: &mut B, slide: &Slide) -> RenderResult {
+    let window_dimensions = backend.window_size()?;
+    let slide_dimensions = window_dimensions.shrink_rows(FOOTER_ROWS);
+    // Keep the colors changes so these are drawn using the same colors as when the whole slide is.
+    let operations: Vec<_> = slide
+        .render_operations
+        .iter()
+        .filter(|operation| match operation {
+            RenderOperation::SetColors(_) => true,
+            RenderOperation::RenderDynamic(generator) => generator.is_time_dependent(),
+            _ => false,
+        })
+        .cloned()
+        .collect();
+    let mut operator = RenderOperator::new(backend, slide_dimensions, window_dimensions, Default::default());
+    operator.render(&operations)?;
+    backend.flush()?;
+    Ok(())
+}
+
+/// How much a slide overflows the screen.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SlideOverflow {
+    /// The number of rows that don't fit in the screen.
+    ///
+    /// This is also the maximum number of rows the slide can be scrolled down by.
+    pub rows: u16,
+}
+
 impl<W> Drop for TerminalDrawer<W>
 where
     W: io::Write,
 {
     fn drop(&mut self) {
-        let _ = self.handle.queue(terminal::LeaveAlternateScreen);
-        let _ = self.handle.queue(cursor::Show);
+        let handle = self.backend.handle();
+        let _ = handle.queue(terminal::LeaveAlternateScreen);
+        let _ = handle.queue(cursor::Show);
         let _ = disable_raw_mode();
     }
 }
@@ -101,6 +180,12 @@ pub enum RenderError {
     #[error("screen is too small")]
     TerminalTooSmall,
 
+    #[error("slide {0} does not exist")]
+    NoSuchSlide(usize),
+
+    #[error("invalid layout: {0}")]
+    InvalidLayout(&'static str),
+
     #[error(transparent)]
     Other(Box<dyn std::error::Error>),
 }
@@ -0,0 +1,562 @@
+use crate::{
+    render::{
+        backend::RenderBackend,
+        media::{Image, ImageLayout, RenderImageError},
+        properties::{WindowRect, WindowSize},
+    },
+    style::TextStyle,
+    theme::Colors,
+};
+use std::io;
+use unicode_width::UnicodeWidthChar;
+
+/// An in-memory terminal.
+///
+/// This is a [RenderBackend] that draws into a grid of cells rather than into an actual terminal.
+/// This allows rendering presentations without a TTY, e.g. to export them into some other format.
+#[derive(Clone, Debug)]
+pub struct TerminalGrid {
+    rows: Vec<Vec<GridCell>>,
+    dimensions: WindowSize,
+    images: Vec<PlacedImage>,
+    cursor_row: u16,
+    cursor_column: u16,
+    style: CellStyle,
+}
+
+impl TerminalGrid {
+    /// Construct a new, empty, grid that emulates a window of the given size.
+    pub fn new(dimensions: WindowSize) -> Self {
+        let rows = vec![vec![GridCell::default(); dimensions.columns as usize]; dimensions.rows as usize];
+        Self { rows, dimensions, images: Vec::new(), cursor_row: 0, cursor_column: 0, style: CellStyle::default() }
+    }
+
+    /// The number of columns in this grid.
+    pub fn columns(&self) -> u16 {
+        self.dimensions.columns
+    }
+
+    /// The number of rows in this grid.
+    pub fn row_count(&self) -> u16 {
+        self.rows.len() as u16
+    }
+
+    /// Get the rows in this grid.
+    pub fn rows(&self) -> &[Vec<GridCell>] {
+        &self.rows
+    }
+
+    /// Get the images that were drawn into this grid.
+    pub fn images(&self) -> &[PlacedImage] {
+        &self.images
+    }
+
+    /// Get the cell at the given position, if any.
+    pub fn cell(&self, column: u16, row: u16) -> Option<&GridCell> {
+        self.rows.get(row as usize)?.get(column as usize)
+    }
+
+    /// Get the text in a row, ignoring any styling.
+    pub fn row_text(&self, row: u16) -> String {
+        let Some(row) = self.rows.get(row as usize) else {
+            return String::new();
+        };
+        row.iter().map(|cell| cell.character).filter(|c| *c != WIDE_CHARACTER_CONTINUATION).collect()
+    }
+
+    /// The current cursor position as a `(column, row)` tuple.
+    pub fn cursor_position(&self) -> (u16, u16) {
+        (self.cursor_column, self.cursor_row)
+    }
+
+    /// Move the cursor to the given position.
+    pub fn move_cursor(&mut self, column: u16, row: u16) {
+        self.cursor_column = column.min(self.dimensions.columns.saturating_sub(1));
+        self.cursor_row = row.min(self.row_count().saturating_sub(1));
+    }
+
+    fn print(&mut self, character: char) {
+        match character {
+            '\n' => self.cursor_row = (self.cursor_row + 1).min(self.row_count().saturating_sub(1)),
+            '\r' => self.cursor_column = 0,
+            _ if character.is_control() => (),
+            _ => {
+                let width = character.width().unwrap_or(0) as u16;
+                // Characters that can't fit in a row, no matter where they start, are skipped.
+                if width == 0 || width > self.dimensions.columns {
+                    return;
+                }
+                if self.cursor_column + width > self.dimensions.columns {
+                    // Wrap around just like a terminal would.
+                    if self.cursor_row + 1 >= self.row_count() {
+                        self.cursor_column = self.dimensions.columns;
+                        return;
+                    }
+                    self.cursor_column = 0;
+                    self.cursor_row += 1;
+                }
+                let style = self.style.clone();
+                let row = &mut self.rows[self.cursor_row as usize];
+                row[self.cursor_column as usize] = GridCell { character, style: style.clone() };
+                if width == 2 {
+                    row[self.cursor_column as usize + 1] = GridCell { character: WIDE_CHARACTER_CONTINUATION, style };
+                }
+                self.cursor_column += width;
+            }
+        }
+    }
+
+    fn clear_all(&mut self) {
+        let cell = self.blank_cell();
+        for row in &mut self.rows {
+            row.fill(cell.clone());
+        }
+    }
+
+    fn blank_cell(&self) -> GridCell {
+        let colors = Colors { background: self.style.colors.background, foreground: None };
+        GridCell { character: ' ', style: CellStyle { colors, ..Default::default() } }
+    }
+}
+
+impl RenderBackend for TerminalGrid {
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        Ok(self.dimensions.clone())
+    }
+
+    fn cursor_row(&mut self) -> io::Result<u16> {
+        Ok(self.cursor_row)
+    }
+
+    fn move_to(&mut self, column: u16, row: u16) -> io::Result<()> {
+        self.move_cursor(column, row);
+        Ok(())
+    }
+
+    fn move_to_column(&mut self, column: u16) -> io::Result<()> {
+        self.move_cursor(column, self.cursor_row);
+        Ok(())
+    }
+
+    fn move_to_row(&mut self, row: u16) -> io::Result<()> {
+        self.move_cursor(self.cursor_column, row);
+        Ok(())
+    }
+
+    fn move_down(&mut self, amount: u16) -> io::Result<()> {
+        self.move_cursor(self.cursor_column, self.cursor_row.saturating_add(amount));
+        Ok(())
+    }
+
+    fn move_to_next_line(&mut self, amount: u16) -> io::Result<()> {
+        self.move_cursor(0, self.cursor_row.saturating_add(amount));
+        Ok(())
+    }
+
+    fn clear_screen(&mut self) -> io::Result<()> {
+        self.clear_all();
+        self.images.clear();
+        Ok(())
+    }
+
+    fn set_colors(&mut self, colors: &Colors) -> io::Result<()> {
+        if colors.foreground.is_some() {
+            self.style.colors.foreground = colors.foreground;
+        }
+        if colors.background.is_some() {
+            self.style.colors.background = colors.background;
+        }
+        Ok(())
+    }
+
+    fn print_text(&mut self, text: &str, style: &TextStyle) -> io::Result<()> {
+        let previous = self.style.clone();
+        self.style.colors.foreground = style.colors.foreground.or(previous.colors.foreground);
+        self.style.colors.background = style.colors.background.or(previous.colors.background);
+        self.style.bold |= style.is_bold();
+        self.style.italics |= style.is_italics() || style.is_link();
+        self.style.underlined |= style.is_link();
+        self.style.strikethrough |= style.is_strikethrough();
+        self.style.url = style.url.clone().or(previous.url.clone());
+        for character in text.chars() {
+            self.print(character);
+        }
+        self.style = previous;
+        Ok(())
+    }
+
+    fn draw_image(&mut self, image: &Image, rect: &WindowRect) -> Result<(), RenderImageError> {
+        let row = self.cursor_row;
+        let ImageLayout { start_column, width_in_columns, height_in_rows } = ImageLayout::compute(image, rect, row);
+        let (columns, rows) = (width_in_columns as u16, height_in_rows as u16);
+        self.images.push(PlacedImage { image: image.clone(), column: start_column, row, columns, rows });
+        self.move_cursor(0, row.saturating_add(rows));
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// An image drawn into a [TerminalGrid].
+#[derive(Clone, Debug)]
+pub struct PlacedImage {
+    /// The image itself.
+    pub image: Image,
+
+    /// The column the image starts at.
+    pub column: u16,
+
+    /// The row the image starts at.
+    pub row: u16,
+
+    /// The number of columns the image takes up.
+    pub columns: u16,
+
+    /// The number of rows the image takes up.
+    pub rows: u16,
+}
+
+/// The character used in the cell right after a character that's 2 columns wide.
+pub const WIDE_CHARACTER_CONTINUATION: char = '\0';
+
+/// A cell in a [TerminalGrid].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GridCell {
+    /// The character in this cell.
+    pub character: char,
+
+    /// The style this cell was printed with.
+    pub style: CellStyle,
+}
+
+impl Default for GridCell {
+    fn default() -> Self {
+        Self { character: ' ', style: CellStyle::default() }
+    }
+}
+
+/// The style of a cell in a [TerminalGrid].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CellStyle {
+    /// The cell's colors.
+    pub colors: Colors,
+
+    /// Whether this cell is bold.
+    pub bold: bool,
+
+    /// Whether this cell uses italics.
+    pub italics: bool,
+
+    /// Whether this cell is underlined.
+    pub underlined: bool,
+
+    /// Whether this cell is struck through.
+    pub strikethrough: bool,
+
+    /// The URL this cell links to, if any.
+    pub url: Option<String>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        export::SlideRenderer,
+        markdown::{
+            elements::StyledText,
+            text::{WeightedLine, WeightedText},
+        },
+        presentation::{AsRenderOperations, Presentation, RenderOperation, Slide},
+        render::draw::{RenderError, SlideOverflow, render_slide, render_time_dependent},
+        theme::Alignment,
+    };
+    use crossterm::style::Color;
+    use rstest::rstest;
+    use std::{cell::Cell, rc::Rc};
+
+    fn make_grid(columns: u16, rows: u16) -> TerminalGrid {
+        TerminalGrid::new(WindowSize { rows, columns, width: columns * 8, height: rows * 16 })
+    }
+
+    #[test]
+    fn print_text() {
+        let mut grid = make_grid(10, 2);
+        grid.move_to(2, 1).unwrap();
+        grid.print_text("hi", &TextStyle::default()).unwrap();
+        assert_eq!(grid.row_text(0), " ".repeat(10));
+        assert_eq!(grid.row_text(1), "  hi      ");
+        assert_eq!(grid.cursor_position(), (4, 1));
+    }
+
+    #[test]
+    fn cursor_movements() {
+        let mut grid = make_grid(10, 5);
+        grid.move_to_row(2).unwrap();
+        grid.move_to_column(4).unwrap();
+        assert_eq!(grid.cursor_position(), (4, 2));
+        grid.move_down(1).unwrap();
+        assert_eq!(grid.cursor_position(), (4, 3));
+        grid.move_to_next_line(1).unwrap();
+        assert_eq!(grid.cursor_position(), (0, 4));
+        // Moving past the bottom stays in the last row.
+        grid.move_down(3).unwrap();
+        assert_eq!(grid.cursor_position(), (0, 4));
+    }
+
+    #[test]
+    fn clear_uses_background() {
+        let mut grid = make_grid(3, 2);
+        grid.print_text("abc", &TextStyle::default()).unwrap();
+        grid.set_colors(&Colors { foreground: None, background: Some(Color::Blue) }).unwrap();
+        grid.clear_screen().unwrap();
+        for row in grid.rows() {
+            for cell in row {
+                assert_eq!(cell.character, ' ');
+                assert_eq!(cell.style.colors.background, Some(Color::Blue));
+            }
+        }
+    }
+
+    #[test]
+    fn wide_characters() {
+        let mut grid = make_grid(4, 1);
+        grid.print_text("日x", &TextStyle::default()).unwrap();
+        assert_eq!(grid.cell(1, 0).unwrap().character, WIDE_CHARACTER_CONTINUATION);
+        assert_eq!(grid.row_text(0), "日x ");
+    }
+
+    #[test]
+    fn wide_characters_in_narrow_grid() {
+        let mut grid = make_grid(1, 2);
+        grid.print_text("日x", &TextStyle::default()).unwrap();
+        assert_eq!(grid.row_text(0), "x");
+        assert_eq!(grid.row_text(1), " ");
+    }
+
+    #[test]
+    fn clips_at_bottom_right() {
+        let mut grid = make_grid(2, 2);
+        grid.print_text("abcdef", &TextStyle::default()).unwrap();
+        assert_eq!(grid.row_text(0), "ab");
+        assert_eq!(grid.row_text(1), "cd");
+    }
+
+    #[test]
+    fn backend_styled_text() {
+        let mut grid = make_grid(10, 1);
+        grid.set_colors(&Colors { foreground: Some(Color::Red), background: Some(Color::Black) }).unwrap();
+        let style = TextStyle::default()
+            .link("https://example.com")
+            .colors(Colors { foreground: Some(Color::Blue), background: None });
+        grid.print_text("a", &style).unwrap();
+        grid.print_text("b", &TextStyle::default()).unwrap();
+
+        let link = &grid.cell(0, 0).unwrap().style;
+        assert!(link.italics && link.underlined);
+        assert_eq!(link.url.as_deref(), Some("https://example.com"));
+        assert_eq!(link.colors, Colors { foreground: Some(Color::Blue), background: Some(Color::Black) });
+
+        // Styles only apply to the text they're printed with.
+        let expected_colors = Colors { foreground: Some(Color::Red), background: Some(Color::Black) };
+        assert_eq!(grid.cell(1, 0).unwrap().style, CellStyle { colors: expected_colors, ..Default::default() });
+    }
+
+    #[test]
+    fn backend_images() {
+        let mut grid = make_grid(40, 10);
+        grid.move_cursor(3, 2);
+        let image = Image::from(image::DynamicImage::new_rgb8(80, 32));
+        let rect = WindowRect::from(grid.window_size().unwrap());
+        grid.draw_image(&image, &rect).unwrap();
+
+        let placed = &grid.images()[0];
+        assert_eq!((placed.column, placed.row, placed.columns, placed.rows), (15, 2, 10, 2));
+        assert_eq!(grid.cursor_position(), (0, 4));
+    }
+
+    fn text_slide(text: &str, alignment: Alignment) -> Slide {
+        let line = WeightedLine::from(vec![WeightedText::from(StyledText::from(text))]);
+        let render_operations = vec![
+            RenderOperation::ClearScreen,
+            RenderOperation::RenderTextLine { line, alignment },
+            RenderOperation::RenderLineBreak,
+        ];
+        Slide { render_operations, notes: Vec::new(), after_pause: false }
+    }
+
+    #[test]
+    fn render_slides() {
+        let presentation = Presentation::new(vec![
+            text_slide("first", Alignment::Center { minimum_size: 0, minimum_margin: 0 }),
+            text_slide("this is the second slide", Alignment::Left { margin: 2 }),
+        ]);
+        let dimensions = WindowSize { rows: 6, columns: 15, width: 120, height: 96 };
+
+        let grid = SlideRenderer::new(dimensions.clone()).render_slide_at(&presentation, 0).expect("render failed");
+        assert_eq!(grid.row_text(0), "     first     ");
+
+        // The text is wrapped given it doesn't fit within the margins.
+        let grid = SlideRenderer::new(dimensions.clone()).render_slide_at(&presentation, 1).expect("render failed");
+        let rows: Vec<_> = (0..4).map(|row| grid.row_text(row)).collect();
+        assert_eq!(rows, &["  this is the  ", "  second       ", "  slide        ", " ".repeat(15).as_str()]);
+
+        let result = SlideRenderer::new(dimensions).render_slide_at(&presentation, 2);
+        assert!(matches!(result, Err(RenderError::NoSuchSlide(2))));
+    }
+
+    #[test]
+    fn render_columns() {
+        let text = |text: &str| RenderOperation::RenderTextLine {
+            line: WeightedLine::from(vec![WeightedText::from(StyledText::from(text))]),
+            alignment: Alignment::Left { margin: 1 },
+        };
+        let render_operations = vec![
+            RenderOperation::ClearScreen,
+            RenderOperation::InitColumnLayout { columns: vec![1, 1] },
+            RenderOperation::EnterColumn { column: 0 },
+            text("a"),
+            RenderOperation::RenderLineBreak,
+            text("b"),
+            RenderOperation::RenderLineBreak,
+            RenderOperation::EnterColumn { column: 1 },
+            text("c"),
+            RenderOperation::RenderLineBreak,
+            RenderOperation::ExitLayout,
+            text("d"),
+        ];
+        let presentation = Presentation::new(vec![Slide { render_operations, notes: Vec::new(), after_pause: false }]);
+        let dimensions = WindowSize { rows: 6, columns: 20, width: 160, height: 96 };
+        let grid = SlideRenderer::new(dimensions).render_slide_at(&presentation, 0).expect("render failed");
+        let rows: Vec<_> = (0..3).map(|row| grid.row_text(row)).collect();
+        assert_eq!(rows, &[" a         c        ", " b                  ", " d                  "]);
+    }
+
+    fn text(text: &str) -> RenderOperation {
+        RenderOperation::RenderTextLine {
+            line: WeightedLine::from(vec![WeightedText::from(StyledText::from(text))]),
+            alignment: Alignment::Left { margin: 1 },
+        }
+    }
+
+    #[derive(Debug)]
+    struct Lines(Vec<&'static str>);
+
+    impl AsRenderOperations for Lines {
+        fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
+            self.0.iter().flat_map(|line| [text(line), RenderOperation::RenderLineBreak]).collect()
+        }
+    }
+
+    #[test]
+    fn jump_to_middle() {
+        let render_operations = vec![
+            RenderOperation::ClearScreen,
+            RenderOperation::RenderLineBreak,
+            RenderOperation::JumpToMiddle,
+            text("a"),
+            RenderOperation::RenderLineBreak,
+            text("b"),
+            RenderOperation::RenderLineBreak,
+            RenderOperation::RenderLineBreak,
+        ];
+        let presentation = Presentation::new(vec![Slide { render_operations, notes: Vec::new(), after_pause: false }]);
+        // The slide itself is 3 rows shorter than the window.
+        let dimensions = WindowSize { rows: 9, columns: 10, width: 80, height: 144 };
+        let grid = SlideRenderer::new(dimensions).render_slide_at(&presentation, 0).expect("render failed");
+        let rows: Vec<_> = (0..6).map(|row| grid.row_text(row)).collect();
+        let empty = " ".repeat(10);
+        assert_eq!(rows, &[&empty, &empty, " a        ", " b        ", &empty, &empty]);
+    }
+
+    #[rstest]
+    #[case::room_left(&["a"], &[" a        ", "          ", "          ", " f        "])]
+    #[case::no_room_left(&["a", "b", "c"], &[" a        ", " b        ", " c        ", " f        "])]
+    fn jump_to_bottom(#[case] lines: &[&str], #[case] expected: &[&str]) {
+        let mut render_operations = vec![RenderOperation::ClearScreen];
+        for line in lines {
+            render_operations.extend([text(line), RenderOperation::RenderLineBreak]);
+        }
+        render_operations.extend([RenderOperation::JumpToBottom, text("f"), RenderOperation::RenderLineBreak]);
+        let presentation = Presentation::new(vec![Slide { render_operations, notes: Vec::new(), after_pause: false }]);
+        // The slide itself is 3 rows shorter than the window.
+        let dimensions = WindowSize { rows: 7, columns: 10, width: 80, height: 112 };
+        let grid = SlideRenderer::new(dimensions).render_slide_at(&presentation, 0).expect("render failed");
+        let rows: Vec<_> = (0..4).map(|row| grid.row_text(row)).collect();
+        assert_eq!(rows, expected);
+    }
+
+    #[test]
+    fn jump_to_middle_dynamic() {
+        let render_operations = vec![
+            RenderOperation::ClearScreen,
+            RenderOperation::JumpToMiddle,
+            RenderOperation::RenderDynamic(Rc::new(Lines(vec!["a", "b", "c", "d"]))),
+        ];
+        let presentation = Presentation::new(vec![Slide { render_operations, notes: Vec::new(), after_pause: false }]);
+        let dimensions = WindowSize { rows: 9, columns: 10, width: 80, height: 144 };
+        let grid = SlideRenderer::new(dimensions).render_slide_at(&presentation, 0).expect("render failed");
+        let rows: Vec<_> = (0..6).map(|row| grid.row_text(row)).collect();
+        let empty = " ".repeat(10);
+        assert_eq!(rows, &[&empty, " a        ", " b        ", " c        ", " d        ", &empty]);
+    }
+
+    #[derive(Debug, Default)]
+    struct Ticks(Cell<u32>);
+
+    impl AsRenderOperations for Ticks {
+        fn as_render_operations(&self, _: &WindowSize) -> Vec<RenderOperation> {
+            let ticks = self.0.get();
+            self.0.set(ticks + 1);
+            vec![RenderOperation::JumpToWindowBottom, text(&ticks.to_string())]
+        }
+
+        fn is_time_dependent(&self) -> bool {
+            true
+        }
+    }
+
+    #[test]
+    fn time_dependent_redraw() {
+        let render_operations = vec![
+            RenderOperation::ClearScreen,
+            text("a"),
+            RenderOperation::RenderLineBreak,
+            RenderOperation::RenderDynamic(Rc::new(Ticks::default())),
+        ];
+        let slide = Slide { render_operations, notes: Vec::new(), after_pause: false };
+        let mut grid = make_grid(5, 5);
+        render_slide(&mut grid, &slide, 0).expect("render failed");
+        assert_eq!(grid.row_text(4), " 0   ");
+
+        // Only the time dependent operations are drawn again.
+        grid.move_to(1, 0).unwrap();
+        grid.print_text("b", &TextStyle::default()).unwrap();
+        render_time_dependent(&mut grid, &slide).expect("render failed");
+        assert_eq!(grid.row_text(0), " b   ");
+        assert_eq!(grid.row_text(4), " 1   ");
+    }
+
+    #[test]
+    fn overflowing_slide() {
+        let mut render_operations = vec![RenderOperation::ClearScreen];
+        for line in ["a", "b", "c", "d", "e"] {
+            render_operations.extend([text(line), RenderOperation::RenderLineBreak]);
+        }
+        let slide = Slide { render_operations, notes: Vec::new(), after_pause: false };
+        // This leaves 3 rows for the slide itself.
+        let dimensions = WindowSize { rows: 6, columns: 10, width: 80, height: 96 };
+
+        let mut grid = TerminalGrid::new(dimensions.clone());
+        let overflow = render_slide(&mut grid, &slide, 0).expect("render failed");
+        assert_eq!(overflow, SlideOverflow { rows: 2 });
+        let rows: Vec<_> = (0..4).map(|row| grid.row_text(row)).collect();
+        assert_eq!(rows, &[" a        ", " b        ", " c        ", "        ▼ "]);
+
+        let mut grid = TerminalGrid::new(dimensions);
+        render_slide(&mut grid, &slide, 2).expect("render failed");
+        let rows: Vec<_> = (0..4).map(|row| grid.row_text(row)).collect();
+        assert_eq!(rows, &[" c        ", " d        ", " e        ", "       ▲  "]);
+    }
+}
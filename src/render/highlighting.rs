@@ -1,10 +1,15 @@
-use crate::markdown::elements::ProgrammingLanguage;
+use crate::{
+    markdown::elements::{ProgrammingLanguage, StyledText},
+    style::TextStyle,
+    theme::Colors,
+};
+use crossterm::style::Color;
 use once_cell::sync::Lazy;
 use syntect::{
     easy::HighlightLines,
-    highlighting::{Style, Theme, ThemeSet},
+    highlighting::{FontStyle, Style, Theme, ThemeSet},
     parsing::SyntaxSet,
-    util::{as_24_bit_terminal_escaped, LinesWithEndings},
+    util::LinesWithEndings,
 };
 
 static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(SyntaxSet::load_defaults_newlines);
@@ -33,13 +38,37 @@ impl CodeHighlighter {
         let mut lines = Vec::new();
         for line in LinesWithEndings::from(code) {
             let ranges: Vec<(Style, &str)> = highlight_lines.highlight_line(line, &SYNTAX_SET).unwrap();
-            let escaped = as_24_bit_terminal_escaped(&ranges, true);
-            let code_line = CodeLine { original: line, formatted: escaped };
+            let formatted = ranges.iter().map(|(style, text)| Self::styled_text(*style, text)).collect();
+            let dimmed = ranges.iter().map(|(style, text)| Self::styled_text(Self::dim(*style), text)).collect();
+            let code_line = CodeLine { original: line, formatted, dimmed };
             lines.push(code_line);
         }
         lines
     }
 
+    fn styled_text(style: Style, text: &str) -> StyledText {
+        let color = |color: syntect::highlighting::Color| Color::Rgb { r: color.r, g: color.g, b: color.b };
+        let colors = Colors { foreground: Some(color(style.foreground)), background: Some(color(style.background)) };
+        let mut text_style = TextStyle::default().colors(colors);
+        if style.font_style.contains(FontStyle::BOLD) {
+            text_style = text_style.bold();
+        }
+        if style.font_style.contains(FontStyle::ITALIC) {
+            text_style = text_style.italics();
+        }
+        StyledText::new(text, text_style)
+    }
+
+    // Dims a style by moving its foreground color most of the way towards its background color.
+    fn dim(mut style: Style) -> Style {
+        let blend = |foreground: u8, background: u8| ((foreground as u16 + background as u16 * 2) / 3) as u8;
+        let (foreground, background) = (style.foreground, style.background);
+        style.foreground.r = blend(foreground.r, background.r);
+        style.foreground.g = blend(foreground.g, background.g);
+        style.foreground.b = blend(foreground.b, background.b);
+        style
+    }
+
     fn language_extension(language: &ProgrammingLanguage) -> &'static str {
         use ProgrammingLanguage::*;
         match language {
@@ -86,10 +115,11 @@ pub struct CodeLine<'a> {
     /// The original line of code.
     pub original: &'a str,
 
-    /// The formatted line of code.
-    ///
-    /// This uses terminal escape codes internally and is ready to be printed.
-    pub formatted: String,
+    /// The highlighted line of code, split into pieces of text that share the same style.
+    pub formatted: Vec<StyledText>,
+
+    /// The highlighted line of code, dimmed so it stands out less than highlighted lines.
+    pub dimmed: Vec<StyledText>,
 }
 
 /// A theme could not be found.
@@ -1,12 +1,25 @@
 use crate::{render::properties::WindowSize, theme::Alignment};
 
-pub(crate) struct Layout<'a>(pub(crate) &'a Alignment);
+pub(crate) struct Layout<'a> {
+    alignment: &'a Alignment,
+    start_column: u16,
+}
 
 impl<'a> Layout<'a> {
+    pub(crate) fn new(alignment: &'a Alignment) -> Self {
+        Self { alignment, start_column: 0 }
+    }
+
+    /// Compute positions relative to the given column rather than the left edge of the window.
+    pub(crate) fn with_start_column(mut self, column: u16) -> Self {
+        self.start_column = column;
+        self
+    }
+
     pub(crate) fn compute(&self, dimensions: &WindowSize, text_length: u16) -> Positioning {
         let max_line_length;
         let mut start_column;
-        match *self.0 {
+        match *self.alignment {
             Alignment::Left { margin } => {
                 // Ignore the margin if it's larger than the screen: we can't satisfy it so we
                 // might as well not do anything about it.
@@ -37,7 +50,7 @@ impl<'a> Layout<'a> {
                 }
             }
         };
-        Positioning { max_line_length, start_column }
+        Positioning { max_line_length, start_column: start_column + self.start_column }
     }
 
     fn fit_to_columns(dimensions: &WindowSize, required_fit: u16, actual_fit: u16) -> u16 {
@@ -144,7 +157,15 @@ mod test {
     )]
     fn layout(#[case] alignment: Alignment, #[case] length: u16, #[case] expected: Positioning) {
         let dimensions = WindowSize { rows: 0, columns: 100, width: 0, height: 0 };
-        let positioning = Layout(&alignment).compute(&dimensions, length);
+        let positioning = Layout::new(&alignment).compute(&dimensions, length);
         assert_eq!(positioning, expected);
     }
+
+    #[test]
+    fn start_column() {
+        let dimensions = WindowSize { rows: 0, columns: 50, width: 0, height: 0 };
+        let alignment = Alignment::Center { minimum_margin: 0, minimum_size: 0 };
+        let positioning = Layout::new(&alignment).with_start_column(50).compute(&dimensions, 10);
+        assert_eq!(positioning, Positioning { max_line_length: 10, start_column: 70 });
+    }
 }
@@ -1,4 +1,4 @@
-use crate::render::properties::WindowSize;
+use crate::render::properties::WindowRect;
 use crossterm::cursor;
 use image::{DynamicImage, ImageError};
 use std::{fmt::Debug, io, rc::Rc};
@@ -23,6 +23,17 @@ impl Image {
         let contents = Rc::new(contents);
         Ok(Self(contents))
     }
+
+    /// Get the underlying image.
+    pub fn contents(&self) -> &DynamicImage {
+        &self.0
+    }
+}
+
+impl From<DynamicImage> for Image {
+    fn from(image: DynamicImage) -> Self {
+        Self(Rc::new(image))
+    }
 }
 
 /// A media render.
@@ -37,9 +48,33 @@ impl MediaRender {
     ///
     /// In case the image does not fit, it will be resized to fit the screen, preserving the aspect
     /// ratio.
-    pub fn draw_image(&self, image: &Image, dimensions: &WindowSize) -> Result<(), RenderImageError> {
+    pub fn draw_image(&self, image: &Image, rect: &WindowRect) -> Result<(), RenderImageError> {
         let position = cursor::position()?;
+        let ImageLayout { start_column, width_in_columns, .. } = ImageLayout::compute(image, rect, position.1);
+        let config = viuer::Config {
+            width: Some(width_in_columns),
+            x: start_column,
+            y: position.1 as i16,
+            ..Default::default()
+        };
+        viuer::print(&image.0, &config)?;
+        Ok(())
+    }
+}
+
+/// The position and size an image is drawn with.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub(crate) struct ImageLayout {
+    pub(crate) start_column: u16,
+    pub(crate) width_in_columns: u32,
+    pub(crate) height_in_rows: u32,
+}
+
+impl ImageLayout {
+    /// Compute the layout for an image that's drawn starting at the given row.
+    pub(crate) fn compute(image: &Image, rect: &WindowRect, row: u16) -> Self {
         let image = &image.0;
+        let dimensions = &rect.dimensions;
 
         // Compute the image's width in columns by translating pixels -> columns.
         let column_in_pixels = dimensions.pixels_per_column();
@@ -51,7 +86,7 @@ impl MediaRender {
         let height_in_rows = (image.height() as f64 / row_in_pixels) as u32;
 
         // If the image doesn't fit vertically, shrink it.
-        let available_height = dimensions.rows.saturating_sub(position.1) as u32;
+        let available_height = dimensions.rows.saturating_sub(row) as u32;
         if height_in_rows > available_height {
             // Because we only use the width to draw, here we scale the width based on how much we
             // need to shrink the height.
@@ -61,16 +96,14 @@ impl MediaRender {
         // Don't go too far wide.
         let width_in_columns = width_in_columns.min(column_margin);
 
+        // The height follows from the width given the aspect ratio is preserved.
+        let width_in_pixels = width_in_columns as f64 * column_in_pixels;
+        let height_in_pixels = width_in_pixels * image.height() as f64 / image.width().max(1) as f64;
+        let height_in_rows = (height_in_pixels / row_in_pixels).ceil() as u32;
+
         // Draw it in the middle
-        let start_column = dimensions.columns / 2 - (width_in_columns / 2) as u16;
-        let config = viuer::Config {
-            width: Some(width_in_columns),
-            x: start_column,
-            y: position.1 as i16,
-            ..Default::default()
-        };
-        viuer::print(image, &config)?;
-        Ok(())
+        let start_column = rect.start_column + dimensions.columns / 2 - (width_in_columns / 2) as u16;
+        Self { start_column, width_in_columns, height_in_rows }
     }
 }
 
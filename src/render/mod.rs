@@ -1,7 +1,10 @@
+pub mod backend;
 pub mod draw;
+pub mod grid;
 pub mod highlighting;
 pub mod layout;
 pub mod media;
 pub mod operator;
 pub mod properties;
 pub mod text;
+pub(crate) mod viewport;
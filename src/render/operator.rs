@@ -1,61 +1,89 @@
 use super::{
+    backend::RenderBackend,
     draw::{RenderError, RenderResult},
     layout::Layout,
-    media::{Image, MediaRender},
+    media::{Image, ImageLayout},
     text::TextDrawer,
+    viewport::Viewport,
 };
 use crate::{
     markdown::text::WeightedLine,
     presentation::{AsRenderOperations, PreformattedLine, RenderOperation},
-    render::{layout::Positioning, properties::WindowSize},
+    render::{
+        layout::Positioning,
+        properties::{WindowRect, WindowSize},
+    },
+    style::TextStyle,
     theme::{Alignment, Colors},
 };
-use crossterm::{
-    cursor, style,
-    terminal::{self, ClearType},
-    QueueableCommand,
-};
-use std::io;
+use std::borrow::Cow;
 
-pub(crate) struct RenderOperator<'a, W> {
-    handle: &'a mut W,
+pub(crate) struct RenderOperator<'a, B> {
+    backend: Viewport<'a, B>,
     slide_dimensions: WindowSize,
     window_dimensions: WindowSize,
     colors: Colors,
+    layout: LayoutState,
 }
 
-impl<'a, W> RenderOperator<'a, W>
+impl<'a, B> RenderOperator<'a, B>
 where
-    W: io::Write,
+    B: RenderBackend,
 {
     pub(crate) fn new(
-        handle: &'a mut W,
+        backend: &'a mut B,
         slide_dimensions: WindowSize,
         window_dimensions: WindowSize,
         colors: Colors,
     ) -> Self {
-        Self { handle, slide_dimensions, window_dimensions, colors }
+        let backend = Viewport::new(backend, 0, slide_dimensions.rows);
+        Self { backend, slide_dimensions, window_dimensions, colors, layout: LayoutState::Default }
+    }
+
+    /// Scroll the slide down by the given number of rows.
+    pub(crate) fn with_scroll_offset(mut self, offset: u16) -> Self {
+        self.backend = self.backend.with_offset(offset);
+        self
+    }
+
+    /// The number of rows the content rendered so far takes up.
+    pub(crate) fn content_height(&self) -> u16 {
+        self.backend.content_height()
     }
 
-    pub(crate) fn render(&mut self, operation: &RenderOperation) -> RenderResult {
+    pub(crate) fn render(&mut self, operations: &[RenderOperation]) -> RenderResult {
+        for (index, operation) in operations.iter().enumerate() {
+            self.render_operation(operation, &operations[index + 1..])?;
+        }
+        Ok(())
+    }
+
+    fn render_operation(&mut self, operation: &RenderOperation, remaining: &[RenderOperation]) -> RenderResult {
         match operation {
             RenderOperation::ClearScreen => self.clear_screen(),
             RenderOperation::SetColors(colors) => self.set_colors(colors),
             RenderOperation::JumpToVerticalCenter => self.jump_to_vertical_center(),
+            RenderOperation::JumpToMiddle => self.jump_to_middle(remaining),
+            RenderOperation::JumpToBottom => self.jump_to_bottom(remaining),
             RenderOperation::JumpToSlideBottom => self.jump_to_slide_bottom(),
             RenderOperation::JumpToWindowBottom => self.jump_to_window_bottom(),
+            RenderOperation::InitColumnLayout { columns } => self.init_column_layout(columns),
+            RenderOperation::EnterColumn { column } => self.enter_column(*column),
+            RenderOperation::ExitLayout => self.exit_layout(),
             RenderOperation::RenderTextLine { line: texts, alignment } => self.render_text(texts, alignment),
             RenderOperation::RenderSeparator => self.render_separator(),
             RenderOperation::RenderLineBreak => self.render_line_break(),
             RenderOperation::RenderImage(image) => self.render_image(image),
             RenderOperation::RenderPreformattedLine(operation) => self.render_preformatted_line(operation),
             RenderOperation::RenderDynamic(generator) => self.render_dynamic(generator.as_ref()),
+            RenderOperation::RenderOnDemand(generator) => self.render_dynamic(generator.as_ref()),
         }
     }
 
     fn clear_screen(&mut self) -> RenderResult {
-        self.handle.queue(terminal::Clear(ClearType::All))?;
-        self.handle.queue(cursor::MoveTo(0, 0))?;
+        self.backend.set_anchored(false);
+        self.backend.clear_screen()?;
+        self.backend.move_to(0, 0)?;
         Ok(())
     }
 
@@ -65,72 +93,219 @@ where
     }
 
     fn apply_colors(&mut self) -> RenderResult {
-        self.handle.queue(style::SetColors(style::Colors {
-            background: self.colors.background,
-            foreground: self.colors.foreground,
-        }))?;
+        self.backend.set_colors(&self.colors)?;
         Ok(())
     }
 
     fn jump_to_vertical_center(&mut self) -> RenderResult {
         let center_row = self.slide_dimensions.rows / 2;
-        self.handle.queue(cursor::MoveToRow(center_row))?;
+        self.backend.move_to_row(center_row)?;
+        Ok(())
+    }
+
+    fn jump_to_middle(&mut self, operations: &[RenderOperation]) -> RenderResult {
+        let height = self.measure_height(operations)?;
+        let row = self.slide_dimensions.rows.saturating_sub(height) / 2;
+        self.backend.move_to_row(row)?;
+        Ok(())
+    }
+
+    fn jump_to_bottom(&mut self, operations: &[RenderOperation]) -> RenderResult {
+        let height = self.measure_height(operations)?;
+        let row = self.slide_dimensions.rows.saturating_sub(height).max(self.backend.cursor_row()?);
+        self.backend.move_to_row(row)?;
         Ok(())
     }
 
+    /// Measure the number of rows the given operations take up once rendered.
+    ///
+    /// This stops at the first operation that moves the cursor to some specific position, as nothing
+    /// after it depends on where the operations before it were drawn.
+    fn measure_height(&self, operations: &[RenderOperation]) -> Result<u16, RenderError> {
+        let slide_rect = WindowRect::from(self.slide_dimensions.clone());
+        let mut rect = slide_rect.clone();
+        let mut layout = LayoutState::Default;
+        let mut row: u16 = 0;
+        let mut height: u16 = 0;
+        // Dynamic operations are expanded as we go since what they generate depends on the
+        // dimensions of the area they're drawn in.
+        let mut operations: Vec<_> = operations.iter().rev().map(Cow::Borrowed).collect();
+        while let Some(operation) = operations.pop() {
+            match operation.as_ref() {
+                RenderOperation::ClearScreen
+                | RenderOperation::JumpToVerticalCenter
+                | RenderOperation::JumpToMiddle
+                | RenderOperation::JumpToBottom
+                | RenderOperation::JumpToSlideBottom
+                | RenderOperation::JumpToWindowBottom => break,
+                RenderOperation::RenderTextLine { line, alignment } => {
+                    let lines = TextDrawer::new(alignment, line, &rect, &self.colors)?.line_count();
+                    if lines > 0 {
+                        height = height.max(row + lines);
+                        row += lines - 1;
+                    }
+                }
+                RenderOperation::RenderPreformattedLine(_) | RenderOperation::RenderSeparator => {
+                    height = height.max(row + 1);
+                }
+                RenderOperation::RenderLineBreak => row += 1,
+                RenderOperation::RenderImage(image) => {
+                    row += ImageLayout::compute(image, &rect, 0).height_in_rows as u16;
+                    height = height.max(row);
+                }
+                RenderOperation::InitColumnLayout { columns } => {
+                    layout =
+                        LayoutState::Columns { columns: columns.clone(), start_row: row, max_row: row, current: None };
+                }
+                RenderOperation::EnterColumn { column } => {
+                    if let LayoutState::Columns { columns, start_row, max_row, current } = &mut layout {
+                        *max_row = (*max_row).max(row);
+                        *current = Some(*column);
+                        row = *start_row;
+                        rect = slide_rect.column(columns, (*column).min(columns.len().saturating_sub(1)));
+                    }
+                }
+                RenderOperation::ExitLayout => {
+                    if let LayoutState::Columns { max_row, .. } = &layout {
+                        row = row.max(*max_row);
+                    }
+                    layout = LayoutState::Default;
+                    rect = slide_rect.clone();
+                }
+                RenderOperation::RenderDynamic(generator) => {
+                    let generated = generator.as_render_operations(&rect.dimensions);
+                    operations.extend(generated.into_iter().rev().map(Cow::Owned));
+                }
+                RenderOperation::RenderOnDemand(generator) => {
+                    let generated = generator.as_render_operations(&rect.dimensions);
+                    operations.extend(generated.into_iter().rev().map(Cow::Owned));
+                }
+                RenderOperation::SetColors(_) => {}
+            };
+        }
+        Ok(height)
+    }
+
     fn jump_to_slide_bottom(&mut self) -> RenderResult {
-        self.handle.queue(cursor::MoveToRow(self.slide_dimensions.rows))?;
+        self.backend.set_anchored(true);
+        self.backend.move_to_row(self.slide_dimensions.rows)?;
         Ok(())
     }
 
     fn jump_to_window_bottom(&mut self) -> RenderResult {
-        self.handle.queue(cursor::MoveToRow(self.window_dimensions.rows))?;
+        self.backend.set_anchored(true);
+        self.backend.move_to_row(self.window_dimensions.rows)?;
         Ok(())
     }
 
+    fn init_column_layout(&mut self, columns: &[u8]) -> RenderResult {
+        if !matches!(self.layout, LayoutState::Default) {
+            self.exit_layout()?;
+        }
+        let start_row = self.backend.cursor_row()?;
+        self.layout = LayoutState::Columns { columns: columns.to_vec(), start_row, max_row: start_row, current: None };
+        Ok(())
+    }
+
+    fn enter_column(&mut self, column: usize) -> RenderResult {
+        let LayoutState::Columns { columns, start_row, max_row, current } = &mut self.layout else {
+            return Err(RenderError::InvalidLayout("column entered without a column layout"));
+        };
+        if column >= columns.len() {
+            return Err(RenderError::InvalidLayout("column index is out of bounds"));
+        }
+        // Keep track of how far down the column we're leaving went.
+        if current.is_some() {
+            *max_row = (*max_row).max(self.backend.cursor_row()?);
+        }
+        *current = Some(column);
+        let start_row = *start_row;
+        let rect = self.current_rect();
+        self.backend.move_to(rect.start_column, start_row)?;
+        Ok(())
+    }
+
+    fn exit_layout(&mut self) -> RenderResult {
+        if let LayoutState::Columns { max_row, current, .. } = &self.layout {
+            let mut max_row = *max_row;
+            if current.is_some() {
+                max_row = max_row.max(self.backend.cursor_row()?);
+            }
+            self.backend.move_to(0, max_row)?;
+        }
+        self.layout = LayoutState::Default;
+        Ok(())
+    }
+
+    fn current_rect(&self) -> WindowRect {
+        let rect = WindowRect::from(self.slide_dimensions.clone());
+        match &self.layout {
+            LayoutState::Columns { columns, current: Some(column), .. } => rect.column(columns, *column),
+            _ => rect,
+        }
+    }
+
     fn render_text(&mut self, text: &WeightedLine, alignment: &Alignment) -> RenderResult {
-        let text_drawer = TextDrawer::new(alignment, text, &self.slide_dimensions, &self.colors)?;
-        text_drawer.draw(&mut self.handle)
+        let rect = self.current_rect();
+        let text_drawer = TextDrawer::new(alignment, text, &rect, &self.colors)?;
+        text_drawer.draw(&mut self.backend)
     }
 
     fn render_separator(&mut self) -> RenderResult {
-        let separator: String = "—".repeat(self.slide_dimensions.columns as usize);
-        self.handle.queue(style::Print(separator))?;
+        let rect = self.current_rect();
+        let separator: String = "—".repeat(rect.dimensions.columns as usize);
+        self.backend.move_to_column(rect.start_column)?;
+        self.backend.print_text(&separator, &TextStyle::default())?;
         Ok(())
     }
 
     fn render_line_break(&mut self) -> RenderResult {
-        self.handle.queue(cursor::MoveToNextLine(1))?;
+        self.backend.move_to_next_line(1)?;
         Ok(())
     }
 
     fn render_image(&mut self, image: &Image) -> RenderResult {
-        MediaRender.draw_image(image, &self.slide_dimensions).map_err(|e| RenderError::Other(Box::new(e)))?;
+        let rect = self.current_rect();
+        self.backend.draw_image(image, &rect).map_err(|e| RenderError::Other(Box::new(e)))?;
         Ok(())
     }
 
     fn render_preformatted_line(&mut self, operation: &PreformattedLine) -> RenderResult {
         let PreformattedLine { text, unformatted_length, block_length, alignment } = operation;
+        let rect = self.current_rect();
         let Positioning { max_line_length, start_column } =
-            Layout(alignment).compute(&self.slide_dimensions, *block_length as u16);
-        self.handle.queue(cursor::MoveToColumn(start_column))?;
+            Layout::new(alignment).with_start_column(rect.start_column).compute(&rect.dimensions, *block_length as u16);
+        self.backend.move_to_column(start_column)?;
 
         let until_right_edge = usize::from(max_line_length).saturating_sub(*unformatted_length);
 
-        // Pad this code block with spaces so we get a nice little rectangle.
-        self.handle.queue(style::Print(&text))?;
-        self.handle.queue(style::Print(" ".repeat(until_right_edge)))?;
+        for chunk in text {
+            self.backend.print_text(&chunk.text, &chunk.style)?;
+        }
+        // Pad this code block with spaces so we get a nice little rectangle. The padding uses the
+        // background of the last piece of text, which for highlighted code is the theme's.
+        let background = text.last().and_then(|chunk| chunk.style.colors.background);
+        let padding_style = TextStyle::default().colors(Colors { foreground: None, background });
+        self.backend.print_text(&" ".repeat(until_right_edge), &padding_style)?;
 
         // Restore colors
         self.apply_colors()?;
         Ok(())
     }
 
-    fn render_dynamic(&mut self, generator: &dyn AsRenderOperations) -> RenderResult {
-        let operations = generator.as_render_operations(&self.slide_dimensions);
-        for operation in operations {
-            self.render(&operation)?;
-        }
-        Ok(())
+    fn render_dynamic<G: AsRenderOperations + ?Sized>(&mut self, generator: &G) -> RenderResult {
+        let operations = generator.as_render_operations(&self.current_rect().dimensions);
+        self.render(&operations)
     }
 }
+
+enum LayoutState {
+    Default,
+    Columns {
+        columns: Vec<u8>,
+        start_row: u16,
+        // The lowest row any of the columns we've left so far reached.
+        max_row: u16,
+        current: Option<usize>,
+    },
+}
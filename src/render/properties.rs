@@ -1,5 +1,5 @@
 use crossterm::terminal::window_size;
-use std::io;
+use std::{io, str::FromStr};
 
 /// The size of the terminal window.
 ///
@@ -34,6 +34,20 @@ impl WindowSize {
         }
     }
 
+    /// Shrink a window by the given number of columns.
+    ///
+    /// This preserves the relationship between columns and pixels.
+    pub fn shrink_columns(&self, amount: u16) -> WindowSize {
+        let pixels_per_column = self.pixels_per_column();
+        let width_to_shrink = (pixels_per_column * amount as f64) as u16;
+        WindowSize {
+            rows: self.rows,
+            columns: self.columns.saturating_sub(amount),
+            height: self.height,
+            width: self.width.saturating_sub(width_to_shrink),
+        }
+    }
+
     /// The number of pixels per column.
     pub fn pixels_per_column(&self) -> f64 {
         self.width as f64 / self.columns as f64
@@ -45,15 +59,73 @@ impl WindowSize {
     }
 }
 
+/// A rectangle within the window that content is drawn into.
+#[derive(Debug, Clone)]
+pub struct WindowRect {
+    /// The dimensions of this rectangle.
+    pub dimensions: WindowSize,
+
+    /// The column in the window where this rectangle starts.
+    pub start_column: u16,
+}
+
+impl WindowRect {
+    /// Get one of the columns that result from splitting this rectangle horizontally.
+    ///
+    /// Every column takes up a portion of the width proportional to its weight.
+    pub fn column(&self, weights: &[u8], index: usize) -> WindowRect {
+        let total: u32 = weights.iter().copied().map(u32::from).sum::<u32>().max(1);
+        let columns = self.dimensions.columns as u32;
+        let offset = |weight: u32| (columns * weight / total) as u16;
+        let start = offset(weights[..index].iter().copied().map(u32::from).sum());
+        let end = offset(weights[..=index].iter().copied().map(u32::from).sum());
+        WindowRect {
+            dimensions: self.dimensions.shrink_columns(self.dimensions.columns - (end - start)),
+            start_column: self.start_column + start,
+        }
+    }
+}
+
+impl From<WindowSize> for WindowRect {
+    fn from(dimensions: WindowSize) -> Self {
+        Self { dimensions, start_column: 0 }
+    }
+}
+
 impl From<crossterm::terminal::WindowSize> for WindowSize {
     fn from(size: crossterm::terminal::WindowSize) -> Self {
         Self { rows: size.rows, columns: size.columns, width: size.width, height: size.height }
     }
 }
 
+impl FromStr for WindowSize {
+    type Err = InvalidWindowSize;
+
+    /// Parse a window size in the `<columns>x<rows>` format, e.g. `80x24`.
+    ///
+    /// Given there's no terminal to query, this assumes every cell is 8 pixels wide and 16 tall.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (columns, rows) = s.split_once('x').ok_or(InvalidWindowSize)?;
+        let columns: u16 = columns.parse().map_err(|_| InvalidWindowSize)?;
+        let rows: u16 = rows.parse().map_err(|_| InvalidWindowSize)?;
+        if columns == 0 || rows == 0 {
+            return Err(InvalidWindowSize);
+        }
+        let width = columns.checked_mul(8).ok_or(InvalidWindowSize)?;
+        let height = rows.checked_mul(16).ok_or(InvalidWindowSize)?;
+        Ok(Self { rows, columns, width, height })
+    }
+}
+
+/// An invalid window size.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid window size, expected <columns>x<rows>")]
+pub struct InvalidWindowSize;
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn shrink() {
@@ -65,4 +137,30 @@ mod test {
         assert_eq!(dimensions.rows, 7);
         assert_eq!(dimensions.height, 70);
     }
+
+    #[test]
+    fn column_rects() {
+        let rect = WindowRect::from(WindowSize { rows: 10, columns: 90, width: 900, height: 100 });
+        let columns: Vec<_> = (0..3)
+            .map(|index| rect.column(&[2, 1, 3], index))
+            .map(|rect| (rect.start_column, rect.dimensions.columns, rect.dimensions.width))
+            .collect();
+        assert_eq!(columns, &[(0, 30, 300), (30, 15, 150), (45, 45, 450)]);
+    }
+
+    #[test]
+    fn parse() {
+        let dimensions: WindowSize = "80x24".parse().expect("parse failed");
+        assert_eq!((dimensions.columns, dimensions.rows, dimensions.width, dimensions.height), (80, 24, 640, 384));
+    }
+
+    #[rstest]
+    #[case::empty("")]
+    #[case::no_separator("80")]
+    #[case::not_numbers("ax24")]
+    #[case::zero("0x24")]
+    #[case::too_large("10000x24")]
+    fn parse_invalid(#[case] input: &str) {
+        assert!(input.parse::<WindowSize>().is_err());
+    }
 }
@@ -1,15 +1,14 @@
 use crate::{
     markdown::text::WeightedLine,
     render::{
+        backend::RenderBackend,
         draw::{RenderError, RenderResult},
         layout::{Layout, Positioning},
-        properties::WindowSize,
+        properties::WindowRect,
     },
     style::TextStyle,
     theme::{Alignment, Colors},
 };
-use crossterm::{cursor, style, QueueableCommand};
-use std::io;
 
 const MINIMUM_LINE_LENGTH: u16 = 10;
 
@@ -26,11 +25,12 @@ impl<'a> TextDrawer<'a> {
     pub fn new(
         alignment: &'a Alignment,
         line: &'a WeightedLine,
-        dimensions: &WindowSize,
+        rect: &WindowRect,
         default_colors: &'a Colors,
     ) -> Result<Self, RenderError> {
         let text_length = line.width() as u16;
-        let positioning = Layout(alignment).compute(dimensions, text_length);
+        let positioning =
+            Layout::new(alignment).with_start_column(rect.start_column).compute(&rect.dimensions, text_length);
         // If our line doesn't fit and it's just too small then abort
         if text_length > positioning.max_line_length && positioning.max_line_length <= MINIMUM_LINE_LENGTH {
             Err(RenderError::TerminalTooSmall)
@@ -39,30 +39,31 @@ impl<'a> TextDrawer<'a> {
         }
     }
 
-    /// Draw text on the given handle.
+    /// The number of rows this text takes up once it's drawn.
+    pub fn line_count(&self) -> u16 {
+        self.line.split(self.positioning.max_line_length as usize).count() as u16
+    }
+
+    /// Draw text on the given backend.
     ///
     /// This performs word splitting and word wrapping.
-    pub fn draw<W: io::Write>(self, handle: &mut W) -> RenderResult {
+    pub fn draw<B: RenderBackend>(self, backend: &mut B) -> RenderResult {
         let Positioning { max_line_length, start_column } = self.positioning;
-        handle.queue(cursor::MoveToColumn(start_column))?;
+        backend.move_to_column(start_column)?;
 
         for (line_index, line) in self.line.split(max_line_length as usize).enumerate() {
-            handle.queue(cursor::MoveToColumn(start_column))?;
+            backend.move_to_column(start_column)?;
             if line_index > 0 {
-                handle.queue(cursor::MoveDown(1))?;
+                backend.move_down(1)?;
             }
             for chunk in line {
                 let (text, style) = chunk.into_parts();
-                let text = style.apply(text);
-                handle.queue(style::PrintStyledContent(text))?;
+                backend.print_text(text, &style)?;
 
                 // Crossterm resets colors if any attributes are set so let's just re-apply colors
                 // if the format has anything on it at all.
                 if style != TextStyle::default() {
-                    handle.queue(style::SetColors(style::Colors {
-                        background: self.default_colors.background,
-                        foreground: self.default_colors.foreground,
-                    }))?;
+                    backend.set_colors(self.default_colors)?;
                 }
             }
         }
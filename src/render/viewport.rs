@@ -0,0 +1,239 @@
+use crate::{
+    render::{
+        backend::RenderBackend,
+        media::{Image, ImageLayout, RenderImageError},
+        properties::{WindowRect, WindowSize},
+    },
+    style::TextStyle,
+    theme::Colors,
+};
+use std::io;
+use unicode_width::UnicodeWidthStr;
+
+/// A backend that only displays a vertical slice of what's drawn into it.
+///
+/// Rows are addressed as if the screen was infinitely tall. Anything drawn into a row that falls
+/// outside of the visible slice is discarded, which allows scrolling through content that doesn't
+/// fit in the screen. This also keeps track of how tall the drawn content is so overflowing
+/// content can be detected.
+pub(crate) struct Viewport<'a, B> {
+    backend: &'a mut B,
+    offset: u16,
+    visible_rows: u16,
+    row: u16,
+    column: u16,
+    synced: bool,
+    anchored: bool,
+    content_height: u16,
+}
+
+impl<'a, B: RenderBackend> Viewport<'a, B> {
+    /// Construct a viewport that shows `visible_rows` rows, starting at row `offset`.
+    pub(crate) fn new(backend: &'a mut B, offset: u16, visible_rows: u16) -> Self {
+        Self { backend, offset, visible_rows, row: 0, column: 0, synced: false, anchored: false, content_height: 0 }
+    }
+
+    /// Set whether positions are anchored to the window rather than to the scrollable content.
+    ///
+    /// While anchored, everything goes straight into the underlying backend. This is meant for
+    /// things like footers which should always be in the same place.
+    pub(crate) fn set_anchored(&mut self, anchored: bool) {
+        self.anchored = anchored;
+        self.synced = false;
+    }
+
+    /// Start showing rows from the given offset.
+    pub(crate) fn with_offset(mut self, offset: u16) -> Self {
+        self.offset = offset;
+        self
+    }
+
+    /// The number of rows taken by the content drawn so far, regardless of whether it's visible.
+    pub(crate) fn content_height(&self) -> u16 {
+        self.content_height
+    }
+
+    fn is_visible(&self, row: u16) -> bool {
+        row >= self.offset && row - self.offset < self.visible_rows
+    }
+
+    fn sync_cursor(&mut self) -> io::Result<()> {
+        if !self.synced {
+            self.backend.move_to(self.column, self.row - self.offset)?;
+            self.synced = true;
+        }
+        Ok(())
+    }
+
+    fn print<F>(&mut self, text: &str, print: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut B) -> io::Result<()>,
+    {
+        if self.anchored {
+            return print(&mut *self.backend);
+        }
+        if !text.is_empty() {
+            self.content_height = self.content_height.max(self.row.saturating_add(1));
+        }
+        if self.is_visible(self.row) {
+            self.sync_cursor()?;
+            print(&mut *self.backend)?;
+        } else {
+            self.synced = false;
+        }
+        Ok(())
+    }
+}
+
+impl<'a, B: RenderBackend> RenderBackend for Viewport<'a, B> {
+    fn window_size(&mut self) -> io::Result<WindowSize> {
+        self.backend.window_size()
+    }
+
+    fn cursor_row(&mut self) -> io::Result<u16> {
+        if self.anchored { self.backend.cursor_row() } else { Ok(self.row) }
+    }
+
+    fn move_to(&mut self, column: u16, row: u16) -> io::Result<()> {
+        if self.anchored {
+            return self.backend.move_to(column, row);
+        }
+        self.column = column;
+        self.row = row;
+        self.synced = false;
+        Ok(())
+    }
+
+    fn move_to_column(&mut self, column: u16) -> io::Result<()> {
+        if self.anchored {
+            return self.backend.move_to_column(column);
+        }
+        self.column = column;
+        self.synced = false;
+        Ok(())
+    }
+
+    fn move_to_row(&mut self, row: u16) -> io::Result<()> {
+        if self.anchored {
+            return self.backend.move_to_row(row);
+        }
+        self.row = row;
+        self.synced = false;
+        Ok(())
+    }
+
+    fn move_down(&mut self, amount: u16) -> io::Result<()> {
+        if self.anchored {
+            return self.backend.move_down(amount);
+        }
+        self.row = self.row.saturating_add(amount);
+        self.synced = false;
+        Ok(())
+    }
+
+    fn move_to_next_line(&mut self, amount: u16) -> io::Result<()> {
+        if self.anchored {
+            return self.backend.move_to_next_line(amount);
+        }
+        self.row = self.row.saturating_add(amount);
+        self.column = 0;
+        self.synced = false;
+        Ok(())
+    }
+
+    fn clear_screen(&mut self) -> io::Result<()> {
+        self.backend.clear_screen()
+    }
+
+    fn set_colors(&mut self, colors: &Colors) -> io::Result<()> {
+        self.backend.set_colors(colors)
+    }
+
+    fn print_text(&mut self, text: &str, style: &TextStyle) -> io::Result<()> {
+        self.print(text, |backend| backend.print_text(text, style))?;
+        self.column = self.column.saturating_add(text.width() as u16);
+        Ok(())
+    }
+
+    fn draw_image(&mut self, image: &Image, rect: &WindowRect) -> Result<(), RenderImageError> {
+        if self.anchored {
+            return self.backend.draw_image(image, rect);
+        }
+        // Lay the image out as if we weren't scrolled so it looks the same no matter the offset.
+        let height = ImageLayout::compute(image, rect, self.row).height_in_rows as u16;
+        // Images can't be partially drawn so they're only displayed if they entirely fit.
+        let last_row = self.row.saturating_add(height).saturating_sub(1);
+        if height > 0 && self.is_visible(self.row) && self.is_visible(last_row) {
+            let rect = WindowRect { dimensions: rect.dimensions.shrink_rows(self.offset), ..rect.clone() };
+            self.sync_cursor()?;
+            self.backend.draw_image(image, &rect)?;
+        }
+        self.row = self.row.saturating_add(height);
+        self.content_height = self.content_height.max(self.row);
+        self.synced = false;
+        Ok(())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.backend.flush()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::render::grid::TerminalGrid;
+    use rstest::rstest;
+
+    fn print_lines<B: RenderBackend>(backend: &mut B, lines: &[&str]) {
+        for line in lines {
+            backend.move_to_column(1).unwrap();
+            backend.print_text(line, &TextStyle::default()).unwrap();
+            backend.move_to_next_line(1).unwrap();
+        }
+    }
+
+    #[test]
+    fn scrolled_content() {
+        let mut grid = TerminalGrid::new(WindowSize { rows: 4, columns: 5, width: 40, height: 64 });
+        let mut viewport = Viewport::new(&mut grid, 0, 2).with_offset(1);
+        print_lines(&mut viewport, &["a", "b", "c", "d"]);
+        assert_eq!(viewport.content_height(), 4);
+
+        let rows: Vec<_> = (0..4).map(|row| grid.row_text(row)).collect();
+        assert_eq!(rows, &[" b   ", " c   ", "     ", "     "]);
+    }
+
+    #[test]
+    fn anchored_content() {
+        let mut grid = TerminalGrid::new(WindowSize { rows: 4, columns: 5, width: 40, height: 64 });
+        let mut viewport = Viewport::new(&mut grid, 2, 2);
+        print_lines(&mut viewport, &["a", "b"]);
+        viewport.set_anchored(true);
+        viewport.move_to(0, 3).unwrap();
+        viewport.print_text("f", &TextStyle::default()).unwrap();
+        // Anchored content isn't part of the slide's content.
+        assert_eq!(viewport.content_height(), 2);
+
+        let rows: Vec<_> = (0..4).map(|row| grid.row_text(row)).collect();
+        assert_eq!(rows, &["     ", "     ", "     ", "f    "]);
+    }
+
+    #[rstest]
+    #[case::above(1, None)]
+    #[case::top(2, Some(0))]
+    #[case::bottom(4, Some(2))]
+    #[case::below(5, None)]
+    fn images_fit(#[case] row: u16, #[case] expected_row: Option<u16>) {
+        let mut grid = TerminalGrid::new(WindowSize { rows: 10, columns: 40, width: 320, height: 160 });
+        let rect = WindowRect::from(grid.window_size().unwrap());
+        let image = Image::from(image::DynamicImage::new_rgb8(80, 32));
+        let mut viewport = Viewport::new(&mut grid, 2, 4);
+        viewport.move_to(0, row).unwrap();
+        viewport.draw_image(&image, &rect).unwrap();
+        assert_eq!(viewport.content_height(), row + 2);
+
+        let rows: Vec<_> = grid.images().iter().map(|image| image.row).collect();
+        assert_eq!(rows, expected_row.into_iter().collect::<Vec<_>>());
+    }
+}
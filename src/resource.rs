@@ -26,6 +26,11 @@ impl Resources {
         Self { base_path: base_path.into(), images: Default::default(), themes: Default::default() }
     }
 
+    /// Get the path relative paths are resolved against.
+    pub fn base_path(&self) -> &Path {
+        &self.base_path
+    }
+
     /// Get the image at the given path.
     pub fn image<P: AsRef<Path>>(&mut self, path: P) -> Result<Image, LoadImageError> {
         let path = self.base_path.join(path);
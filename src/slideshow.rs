@@ -1,62 +1,103 @@
+#[cfg(unix)]
+use crate::sync::SlidePublisher;
 use crate::{
-    builder::{BuildError, PresentationBuilder},
     diff::PresentationDiffer,
     input::{
         source::{Command, CommandSource},
         user::UserCommand,
     },
-    markdown::parse::{MarkdownParser, ParseError},
+    loader::{LoadPresentationError, PresentationLoader},
     presentation::Presentation,
-    render::{
-        draw::{RenderError, RenderResult, TerminalDrawer},
-        highlighting::CodeHighlighter,
-    },
-    resource::Resources,
-    theme::PresentationTheme,
+    render::draw::{RenderError, RenderResult, TerminalDrawer},
 };
 use std::{
-    fs,
     io::{self, Stdout},
     mem,
     path::Path,
+    time::{Duration, Instant},
 };
 
+// How often slides that display the time are redrawn.
+//
+// This is less than a second so no second is ever skipped given commands are polled for up to
+// a quarter of a second.
+const TICK_INTERVAL: Duration = Duration::from_millis(500);
+
 /// A slide show.
 ///
 /// This type puts everything else together.
 pub struct SlideShow<'a> {
-    default_theme: &'a PresentationTheme,
-    default_highlighter: CodeHighlighter,
+    loader: PresentationLoader<'a>,
     commands: CommandSource,
-    parser: MarkdownParser<'a>,
-    resources: Resources,
     mode: SlideShowMode,
     state: SlideShowState,
+    #[cfg(unix)]
+    publisher: Option<SlidePublisher>,
+    scroll: SlideScroll,
+    started_at: Instant,
+    warning: Option<String>,
 }
 
 impl<'a> SlideShow<'a> {
     /// Construct a new slideshow.
-    pub fn new(
-        default_theme: &'a PresentationTheme,
-        default_highlighter: CodeHighlighter,
-        commands: CommandSource,
-        parser: MarkdownParser<'a>,
-        resources: Resources,
-        mode: SlideShowMode,
-    ) -> Self {
-        Self { default_theme, default_highlighter, commands, parser, resources, mode, state: SlideShowState::Empty }
+    pub fn new(loader: PresentationLoader<'a>, commands: CommandSource, mode: SlideShowMode) -> Self {
+        Self {
+            loader,
+            commands,
+            mode,
+            state: SlideShowState::Empty,
+            #[cfg(unix)]
+            publisher: None,
+            scroll: Default::default(),
+            started_at: Instant::now(),
+            warning: None,
+        }
+    }
+
+    /// Publish the slide being presented so presenter views can follow it.
+    #[cfg(unix)]
+    pub fn with_publisher(mut self, publisher: SlidePublisher) -> Self {
+        publisher.set_started_at(self.started_at);
+        self.publisher = Some(publisher);
+        self
+    }
+
+    /// Display a warning at the bottom of the screen while in development mode.
+    pub fn with_warning(mut self, warning: String) -> Self {
+        self.warning = Some(warning);
+        self
     }
 
     /// Run a presentation.
     pub fn present(mut self, path: &Path) -> Result<(), SlideShowError> {
-        self.state = SlideShowState::Presenting(self.load_presentation(path)?);
+        let (presentation, included_paths) = self.loader.load_with_includes(path)?;
+        presentation.start_clock(self.started_at);
+        self.commands.watch(included_paths);
+        self.state = SlideShowState::Presenting(presentation);
 
         let mut drawer = TerminalDrawer::new(io::stdout())?;
         loop {
+            // Check this before rendering so the last state of anything that finishes rendering
+            // while we're drawing still makes it into the screen.
+            let rendering_on_demand = self.is_rendering_on_demand();
+            let time_dependent = self.is_time_dependent();
             self.render(&mut drawer)?;
+            let mut rendered_at = Instant::now();
 
             loop {
-                let command = match self.commands.next_command()? {
+                let Some(command) = self.commands.try_next_command()? else {
+                    // Keep redrawing while there's something rendering in the background.
+                    if rendering_on_demand {
+                        break;
+                    }
+                    // If the slide displays the time, only that part of it needs to be redrawn.
+                    if time_dependent && rendered_at.elapsed() >= TICK_INTERVAL {
+                        self.render_time_dependent(&mut drawer)?;
+                        rendered_at = Instant::now();
+                    }
+                    continue;
+                };
+                let command = match command {
                     Command::User(command) => command,
                     Command::ReloadPresentation => {
                         self.try_reload(path);
@@ -77,7 +118,26 @@ impl<'a> SlideShow<'a> {
 
     fn render(&mut self, drawer: &mut TerminalDrawer<Stdout>) -> RenderResult {
         let result = match &self.state {
-            SlideShowState::Presenting(presentation) => drawer.render_slide(presentation),
+            SlideShowState::Presenting(presentation) => {
+                // Scrolling only applies to the slide it was done in.
+                let slide = presentation.current_slide_index();
+                if self.scroll.slide != slide {
+                    self.scroll = SlideScroll { slide, ..Default::default() };
+                }
+                drawer.render_slide(presentation, self.scroll.offset).and_then(|overflow| {
+                    self.scroll.max_offset = overflow.rows;
+                    self.scroll.offset = self.scroll.offset.min(overflow.rows);
+                    if matches!(self.mode, SlideShowMode::Development) {
+                        if overflow.rows > 0 {
+                            let message = format!("slide {} overflows the screen by {} rows", slide + 1, overflow.rows);
+                            drawer.render_warning(&message)?;
+                        } else if let Some(warning) = &self.warning {
+                            drawer.render_warning(warning)?;
+                        }
+                    }
+                    Ok(())
+                })
+            }
             SlideShowState::Failure { error, .. } => drawer.render_error(error),
             SlideShowState::Empty => panic!("cannot render without state"),
         };
@@ -86,6 +146,28 @@ impl<'a> SlideShow<'a> {
         if matches!(result, Err(RenderError::TerminalTooSmall)) { Ok(()) } else { result }
     }
 
+    fn render_time_dependent(&mut self, drawer: &mut TerminalDrawer<Stdout>) -> RenderResult {
+        let SlideShowState::Presenting(presentation) = &self.state else {
+            return Ok(());
+        };
+        let result = drawer.render_time_dependent(presentation);
+        if matches!(result, Err(RenderError::TerminalTooSmall)) { Ok(()) } else { result }
+    }
+
+    fn is_rendering_on_demand(&self) -> bool {
+        match &self.state {
+            SlideShowState::Presenting(presentation) => presentation.is_rendering_on_demand(),
+            _ => false,
+        }
+    }
+
+    fn is_time_dependent(&self) -> bool {
+        match &self.state {
+            SlideShowState::Presenting(presentation) => presentation.is_time_dependent(),
+            _ => false,
+        }
+    }
+
     fn apply_user_command(&mut self, command: UserCommand) -> CommandSideEffect {
         // This one always happens no matter our state.
         if matches!(command, UserCommand::Exit) {
@@ -101,22 +183,41 @@ impl<'a> SlideShow<'a> {
             UserCommand::JumpFirstSlide => presentation.jump_first_slide(),
             UserCommand::JumpLastSlide => presentation.jump_last_slide(),
             UserCommand::JumpSlide(number) => presentation.jump_slide(number.saturating_sub(1) as usize),
+            UserCommand::ScrollDown => self.scroll.scroll_down(),
+            UserCommand::ScrollUp => self.scroll.scroll_up(),
+            UserCommand::RenderOnDemand => presentation.start_on_demand_renders(),
             UserCommand::Exit => return CommandSideEffect::Exit,
         };
-        if needs_redraw { CommandSideEffect::Redraw } else { CommandSideEffect::None }
+        if needs_redraw {
+            self.publish_current_slide();
+            CommandSideEffect::Redraw
+        } else {
+            CommandSideEffect::None
+        }
+    }
+
+    fn publish_current_slide(&self) {
+        #[cfg(unix)]
+        if let (Some(publisher), SlideShowState::Presenting(presentation)) = (&self.publisher, &self.state) {
+            publisher.publish(presentation.current_slide_index());
+        }
     }
 
     fn try_reload(&mut self, path: &Path) {
         if matches!(self.mode, SlideShowMode::Presentation) {
             return;
         }
-        match self.load_presentation(path) {
-            Ok(mut presentation) => {
+        match self.loader.load_with_includes(path) {
+            Ok((mut presentation, included_paths)) => {
+                // Reloading doesn't restart the presentation.
+                presentation.start_clock(self.started_at);
+                self.commands.watch(included_paths);
                 let current = self.state.presentation();
                 let target_slide = PresentationDiffer::first_modified_slide(current, &presentation)
                     .unwrap_or(current.current_slide_index());
                 presentation.jump_slide(target_slide);
-                self.state = SlideShowState::Presenting(presentation)
+                self.state = SlideShowState::Presenting(presentation);
+                self.publish_current_slide();
             }
             Err(e) => {
                 let presentation = mem::take(&mut self.state).into_presentation();
@@ -124,14 +225,33 @@ impl<'a> SlideShow<'a> {
             }
         };
     }
+}
 
-    fn load_presentation(&mut self, path: &Path) -> Result<Presentation, LoadPresentationError> {
-        let content = fs::read_to_string(path).map_err(LoadPresentationError::Reading)?;
-        let elements = self.parser.parse(&content)?;
-        let presentation =
-            PresentationBuilder::new(self.default_highlighter.clone(), self.default_theme, &mut self.resources)
-                .build(elements)?;
-        Ok(presentation)
+/// The scroll state of the slide being displayed.
+#[derive(Default)]
+struct SlideScroll {
+    slide: usize,
+    offset: u16,
+    max_offset: u16,
+}
+
+impl SlideScroll {
+    fn scroll_down(&mut self) -> bool {
+        if self.offset < self.max_offset {
+            self.offset += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn scroll_up(&mut self) -> bool {
+        if self.offset > 0 {
+            self.offset -= 1;
+            true
+        } else {
+            false
+        }
     }
 }
 
@@ -179,19 +299,6 @@ pub enum SlideShowMode {
     Presentation,
 }
 
-/// An error when loading a presentation.
-#[derive(thiserror::Error, Debug)]
-pub enum LoadPresentationError {
-    #[error(transparent)]
-    Parse(#[from] ParseError),
-
-    #[error("reading presentation: {0}")]
-    Reading(io::Error),
-
-    #[error(transparent)]
-    Processing(#[from] BuildError),
-}
-
 /// An error during the slide show.
 #[derive(thiserror::Error, Debug)]
 pub enum SlideShowError {
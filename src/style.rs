@@ -6,6 +6,9 @@ use crossterm::style::Stylize;
 pub struct TextStyle {
     flags: u8,
     pub colors: Colors,
+
+    /// The URL this text links to, if any.
+    pub url: Option<String>,
 }
 
 impl TextStyle {
@@ -33,9 +36,10 @@ impl TextStyle {
         self
     }
 
-    /// Indicate this is a link.
-    pub fn link(mut self) -> Self {
+    /// Indicate this is a link to the given URL.
+    pub fn link<S: Into<String>>(mut self, url: S) -> Self {
         self.flags |= TextFormatFlags::Link as u8;
+        self.url = Some(url.into());
         self
     }
 
@@ -75,6 +79,9 @@ impl TextStyle {
         self.flags |= other.flags;
         self.colors.background = self.colors.background.or(other.colors.background);
         self.colors.foreground = self.colors.foreground.or(other.colors.foreground);
+        if self.url.is_none() {
+            self.url = other.url.clone();
+        }
     }
 
     /// Apply this style to a piece of text.
@@ -0,0 +1,345 @@
+use std::{
+    collections::hash_map::DefaultHasher,
+    env,
+    fs::{self, DirBuilder},
+    hash::{Hash, Hasher},
+    io::{self, BufRead, BufReader, Write},
+    mem,
+    os::unix::{
+        fs::{DirBuilderExt, MetadataExt},
+        net::{UnixListener, UnixStream},
+    },
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Get the path of the socket used to keep all views of a presentation in sync.
+///
+/// The path is derived from the presentation's path so that every process presenting the same file
+/// ends up using the same socket. Sockets live in a directory only the current user can access so
+/// other users can't pretend to be the presentation.
+pub fn socket_path(presentation_path: &Path) -> io::Result<PathBuf> {
+    let path = presentation_path.canonicalize().unwrap_or_else(|_| presentation_path.into());
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    Ok(socket_directory()?.join(format!("presenterm-{:x}.sock", hasher.finish())))
+}
+
+fn socket_directory() -> io::Result<PathBuf> {
+    if let Some(directory) = env::var_os("XDG_RUNTIME_DIR").filter(|directory| !directory.is_empty()) {
+        let directory = PathBuf::from(directory);
+        verify_private_directory(&directory)?;
+        return Ok(directory);
+    }
+    let directory = env::temp_dir().join(format!("presenterm-{}", current_user()));
+    match DirBuilder::new().mode(0o700).create(&directory) {
+        Err(e) if e.kind() != io::ErrorKind::AlreadyExists => return Err(e),
+        _ => (),
+    };
+    verify_private_directory(&directory)?;
+    Ok(directory)
+}
+
+// Makes sure the given path is a directory owned by and only accessible to the current user.
+fn verify_private_directory(path: &Path) -> io::Result<()> {
+    let metadata = fs::symlink_metadata(path)?;
+    if !metadata.is_dir() || metadata.uid() != current_user() || metadata.mode() & 0o077 != 0 {
+        let message = format!("'{}' is not a directory private to the current user", path.display());
+        return Err(io::Error::new(io::ErrorKind::PermissionDenied, message));
+    }
+    Ok(())
+}
+
+// Makes sure the socket in the given path, if any, was created by the current user.
+fn verify_socket_owner(path: &Path) -> io::Result<()> {
+    match fs::symlink_metadata(path) {
+        Ok(metadata) if metadata.uid() != current_user() => {
+            let message = format!("socket '{}' belongs to another user", path.display());
+            Err(io::Error::new(io::ErrorKind::PermissionDenied, message))
+        }
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+fn current_user() -> u32 {
+    // SAFETY: this has no preconditions and can't fail.
+    unsafe { libc::getuid() }
+}
+
+/// Publishes the current slide so other processes can follow the presentation.
+///
+/// Subscribers are accepted in a background thread and are immediately sent the current slide
+/// when they connect. Along with every slide, the time elapsed since the presentation started is
+/// sent so subscribers can display the same timers as the presentation itself.
+pub struct SlidePublisher {
+    path: PathBuf,
+    socket_id: SocketId,
+    state: Arc<Mutex<PublisherState>>,
+}
+
+impl SlidePublisher {
+    /// Construct a new publisher listening on the given socket path.
+    ///
+    /// This fails if another publisher is already listening on it.
+    pub fn new<P: Into<PathBuf>>(path: P) -> io::Result<Self> {
+        let path = path.into();
+        verify_socket_owner(&path)?;
+        if UnixStream::connect(&path).is_ok() {
+            return Err(io::Error::new(io::ErrorKind::AddrInUse, "presentation is already running"));
+        }
+        // Nobody is listening so this is a socket a previous run that didn't exit cleanly left behind.
+        match fs::remove_file(&path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+            _ => (),
+        };
+        let listener = UnixListener::bind(&path)?;
+        let socket_id = SocketId::new(&path)?;
+        let state: Arc<Mutex<PublisherState>> = Default::default();
+        let thread_state = state.clone();
+        thread::spawn(move || Self::accept_subscribers(listener, thread_state));
+        Ok(Self { path, socket_id, state })
+    }
+
+    /// Set the time the presentation started at.
+    pub fn set_started_at(&self, started_at: Instant) {
+        self.state.lock().expect("lock poisoned").started_at = Some(started_at);
+    }
+
+    /// Publish the index of the slide currently being presented.
+    pub fn publish(&self, slide_index: usize) {
+        let mut state = self.state.lock().expect("lock poisoned");
+        state.current_slide = slide_index;
+        let elapsed = state.elapsed();
+        // Any subscriber we can't write into is gone or isn't keeping up, e.g. because it's stopped.
+        state.subscribers.retain_mut(|subscriber| Self::send(subscriber, slide_index, elapsed).is_ok());
+    }
+
+    fn accept_subscribers(listener: UnixListener, state: Arc<Mutex<PublisherState>>) {
+        for stream in listener.incoming() {
+            // Writes never block so a subscriber that doesn't read can't stall the presentation.
+            let Ok(mut stream) = stream.and_then(|stream| stream.set_nonblocking(true).map(|_| stream)) else {
+                continue;
+            };
+            let (mut slide_index, mut elapsed) = state.lock().expect("lock poisoned").current();
+            loop {
+                if Self::send(&mut stream, slide_index, elapsed).is_err() {
+                    break;
+                }
+                // If the slide changed while we were sending, send it again.
+                let mut state = state.lock().expect("lock poisoned");
+                if state.current_slide == slide_index {
+                    state.subscribers.push(stream);
+                    break;
+                }
+                (slide_index, elapsed) = state.current();
+            }
+        }
+    }
+
+    fn send(stream: &mut UnixStream, slide_index: usize, elapsed: Duration) -> io::Result<()> {
+        let line = format!("{slide_index} {}\n", elapsed.as_millis());
+        stream.write_all(line.as_bytes())
+    }
+}
+
+impl Drop for SlidePublisher {
+    fn drop(&mut self) {
+        // Disconnect subscribers so they know we're gone.
+        if let Ok(mut state) = self.state.lock() {
+            state.subscribers.clear();
+        }
+        // Don't remove the socket if it's no longer the one we created.
+        if SocketId::new(&self.path).ok().as_ref() == Some(&self.socket_id) {
+            let _ = fs::remove_file(&self.path);
+        }
+    }
+}
+
+// Identifies the file a socket was bound to.
+#[derive(PartialEq, Eq)]
+struct SocketId {
+    device: u64,
+    inode: u64,
+}
+
+impl SocketId {
+    fn new(path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        Ok(Self { device: metadata.dev(), inode: metadata.ino() })
+    }
+}
+
+#[derive(Default)]
+struct PublisherState {
+    subscribers: Vec<UnixStream>,
+    current_slide: usize,
+    started_at: Option<Instant>,
+}
+
+impl PublisherState {
+    fn elapsed(&self) -> Duration {
+        self.started_at.map(|started_at| started_at.elapsed()).unwrap_or_default()
+    }
+
+    fn current(&self) -> (usize, Duration) {
+        (self.current_slide, self.elapsed())
+    }
+}
+
+/// Subscribes to the slides published by a [SlidePublisher].
+pub struct SlideSubscriber {
+    reader: BufReader<UnixStream>,
+    buffer: String,
+}
+
+impl SlideSubscriber {
+    /// Connect to the publisher listening on the given socket path.
+    pub fn connect<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        verify_socket_owner(path)?;
+        let stream = UnixStream::connect(path)?;
+        Ok(Self { reader: BufReader::new(stream), buffer: String::new() })
+    }
+
+    /// Wait up to `timeout` for the next event coming from the publisher.
+    pub fn poll_next_event(&mut self, timeout: Duration) -> io::Result<Option<SubscriberEvent>> {
+        self.reader.get_ref().set_read_timeout(Some(timeout))?;
+        match self.reader.read_line(&mut self.buffer) {
+            Ok(0) => Ok(Some(SubscriberEvent::Disconnected)),
+            // If the line is incomplete, keep what we got until the rest of it arrives.
+            Ok(_) if !self.buffer.ends_with('\n') => Ok(None),
+            Ok(_) => {
+                let line = mem::take(&mut self.buffer);
+                Self::parse_event(&line)
+                    .map(Some)
+                    .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "invalid slide event"))
+            }
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn parse_event(line: &str) -> Option<SubscriberEvent> {
+        let (slide, elapsed) = line.trim().split_once(' ')?;
+        let slide = slide.parse().ok()?;
+        let elapsed = Duration::from_millis(elapsed.parse().ok()?);
+        Some(SubscriberEvent::SlideChanged { slide, elapsed })
+    }
+}
+
+/// An event received by a [SlideSubscriber].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SubscriberEvent {
+    /// The presentation moved to another slide.
+    SlideChanged {
+        /// The index of the slide being presented.
+        slide: usize,
+
+        /// The time elapsed since the presentation started.
+        elapsed: Duration,
+    },
+
+    /// The publisher went away.
+    Disconnected,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::{os::unix::fs::PermissionsExt, process};
+
+    fn next_event(subscriber: &mut SlideSubscriber) -> SubscriberEvent {
+        for _ in 0..10 {
+            if let Some(event) = subscriber.poll_next_event(Duration::from_millis(100)).expect("poll failed") {
+                return event;
+            }
+        }
+        panic!("no event received");
+    }
+
+    #[test]
+    fn publish_subscribe() {
+        let path = env::temp_dir().join(format!("presenterm-test-{}.sock", process::id()));
+        let publisher = SlidePublisher::new(&path).expect("bind failed");
+        publisher.publish(2);
+
+        let mut subscriber = SlideSubscriber::connect(&path).expect("connect failed");
+        let expected = SubscriberEvent::SlideChanged { slide: 2, elapsed: Duration::ZERO };
+        assert_eq!(next_event(&mut subscriber), expected);
+
+        let started_at = Instant::now().checked_sub(Duration::from_secs(60)).expect("clock too early");
+        publisher.set_started_at(started_at);
+        publisher.publish(5);
+        let SubscriberEvent::SlideChanged { slide, elapsed } = next_event(&mut subscriber) else {
+            panic!("slide not changed");
+        };
+        assert_eq!(slide, 5);
+        assert!(elapsed >= Duration::from_secs(60), "{elapsed:?}");
+
+        drop(publisher);
+        assert_eq!(next_event(&mut subscriber), SubscriberEvent::Disconnected);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn already_running() {
+        let path = env::temp_dir().join(format!("presenterm-test-running-{}.sock", process::id()));
+        let publisher = SlidePublisher::new(&path).expect("bind failed");
+        let error = SlidePublisher::new(&path).err().expect("second bind succeeded");
+        assert_eq!(error.kind(), io::ErrorKind::AddrInUse);
+
+        // The first publisher is still reachable.
+        let mut subscriber = SlideSubscriber::connect(&path).expect("connect failed");
+        assert_eq!(next_event(&mut subscriber), SubscriberEvent::SlideChanged { slide: 0, elapsed: Duration::ZERO });
+        drop(publisher);
+    }
+
+    #[test]
+    fn stalled_subscriber() {
+        let path = env::temp_dir().join(format!("presenterm-test-stalled-{}.sock", process::id()));
+        let publisher = SlidePublisher::new(&path).expect("bind failed");
+        // This one never reads anything.
+        let _stalled = SlideSubscriber::connect(&path).expect("connect failed");
+        let mut subscriber = SlideSubscriber::connect(&path).expect("connect failed");
+        assert_eq!(next_event(&mut subscriber), SubscriberEvent::SlideChanged { slide: 0, elapsed: Duration::ZERO });
+
+        // Keep changing slides until the stalled subscriber's socket buffer fills up.
+        for slide in 1..1_000_000 {
+            publisher.publish(slide);
+            assert_eq!(next_event(&mut subscriber), SubscriberEvent::SlideChanged { slide, elapsed: Duration::ZERO });
+            if publisher.state.lock().unwrap().subscribers.len() == 1 {
+                return;
+            }
+        }
+        panic!("stalled subscriber was never dropped");
+    }
+
+    #[test]
+    fn private_directory() {
+        let path = env::temp_dir().join(format!("presenterm-test-private-{}", process::id()));
+        DirBuilder::new().mode(0o755).create(&path).expect("creating directory failed");
+        let error = verify_private_directory(&path).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::PermissionDenied);
+
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o700)).expect("setting permissions failed");
+        verify_private_directory(&path).expect("directory not private");
+        fs::remove_dir(&path).expect("removing directory failed");
+    }
+
+    #[test]
+    fn stale_socket() {
+        let path = env::temp_dir().join(format!("presenterm-test-stale-{}.sock", process::id()));
+        // Dropping a listener leaves its socket file behind.
+        drop(UnixListener::bind(&path).expect("bind failed"));
+        assert!(path.exists());
+
+        let publisher = SlidePublisher::new(&path).expect("bind failed");
+        let mut subscriber = SlideSubscriber::connect(&path).expect("connect failed");
+        assert_eq!(next_event(&mut subscriber), SubscriberEvent::SlideChanged { slide: 0, elapsed: Duration::ZERO });
+        drop(publisher);
+        assert!(!path.exists());
+    }
+}
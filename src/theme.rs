@@ -1,6 +1,6 @@
 use crossterm::style::Color;
 use serde::{Deserialize, Serialize};
-use std::{fs, io, path::Path};
+use std::{collections::BTreeMap, fs, io, path::Path};
 
 include!(concat!(env!("OUT_DIR"), "/themes.rs"));
 
@@ -19,22 +19,38 @@ pub struct PresentationTheme {
     #[serde(default)]
     pub code: CodeBlockStyle,
 
+    /// The style for the output of a code block's execution.
+    #[serde(default)]
+    pub execution_output: ExecutionOutputBlockStyle,
+
     /// The style for inline code.
     #[serde(default)]
     pub inline_code: InlineCodeStyle,
 
+    /// The style for links.
+    #[serde(default)]
+    pub link: LinkStyle,
+
     /// The style for a table.
     #[serde(default)]
-    pub table: Option<Alignment>,
+    pub table: TableStyle,
 
     /// The style for a list.
     #[serde(default)]
-    pub list: Option<Alignment>,
+    pub list: ListStyle,
+
+    /// The style for the items in a task list.
+    #[serde(default)]
+    pub task_list: TaskListStyle,
 
     /// The style for a block quote.
     #[serde(default)]
     pub block_quote: BlockQuoteStyle,
 
+    /// The style for an alert.
+    #[serde(default)]
+    pub alert: AlertStyle,
+
     /// The default style.
     ///
     /// This is used as a fallback for any elements that don't have an explicit style.
@@ -52,6 +68,10 @@ pub struct PresentationTheme {
     /// The style of the presentation footer.
     #[serde(default)]
     pub footer: FooterStyle,
+
+    /// The color palette that text can reference.
+    #[serde(default)]
+    pub palette: ColorPalette,
 }
 
 impl PresentationTheme {
@@ -91,13 +111,14 @@ impl PresentationTheme {
             Heading5 => &self.headings.h5.alignment,
             Heading6 => &self.headings.h6.alignment,
             Paragraph => &self.paragraph,
-            List => &self.list,
+            List => &self.list.alignment,
             Code => &self.code.alignment,
             PresentationTitle => &self.intro_slide.title.alignment,
             PresentationSubTitle => &self.intro_slide.subtitle.alignment,
             PresentationAuthor => &self.intro_slide.author.alignment,
-            Table => &self.table,
+            Table => &self.table.alignment,
             BlockQuote => &self.block_quote.alignment,
+            Alert => &self.alert.alignment,
         };
         alignment.clone().or_else(|| self.default_style.alignment.clone()).unwrap_or(Alignment::default())
     }
@@ -191,6 +212,58 @@ pub struct BlockQuoteStyle {
     pub colors: Colors,
 }
 
+/// The style of an alert.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct AlertStyle {
+    /// The alignment.
+    #[serde(flatten, default)]
+    pub alignment: Option<Alignment>,
+
+    /// The prefix to be added to every line of quotes nested within an alert.
+    #[serde(default)]
+    pub prefix: Option<String>,
+
+    /// The colors to be used for the alert's contents.
+    #[serde(default)]
+    pub colors: Colors,
+
+    /// The style for note alerts.
+    #[serde(default)]
+    pub note: AlertTypeStyle,
+
+    /// The style for tip alerts.
+    #[serde(default)]
+    pub tip: AlertTypeStyle,
+
+    /// The style for important alerts.
+    #[serde(default)]
+    pub important: AlertTypeStyle,
+
+    /// The style for warning alerts.
+    #[serde(default)]
+    pub warning: AlertTypeStyle,
+
+    /// The style for caution alerts.
+    #[serde(default)]
+    pub caution: AlertTypeStyle,
+}
+
+/// The style of a specific type of alert.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct AlertTypeStyle {
+    /// The title displayed at the top of alerts that don't have an explicit one.
+    #[serde(default)]
+    pub title: Option<String>,
+
+    /// The icon displayed before the title.
+    #[serde(default)]
+    pub icon: Option<String>,
+
+    /// The color to be used for the border, icon and title.
+    #[serde(default)]
+    pub color: Option<Color>,
+}
+
 /// The style for the presentation introduction slide.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct IntroSlideStyle {
@@ -288,6 +361,12 @@ pub enum FooterStyle {
         /// The colors to be used.
         #[serde(default)]
         colors: Colors,
+
+        /// The colors to be used when the presentation is running behind schedule.
+        ///
+        /// This only applies when the presentation's duration is set in its front matter.
+        #[serde(default)]
+        warning_colors: Option<Colors>,
     },
 
     /// Use a progress bar.
@@ -310,6 +389,7 @@ impl Default for FooterStyle {
             left: Some("{current_slide} / {total_slides}".to_string()),
             right: None,
             colors: Colors::default(),
+            warning_colors: None,
         }
     }
 }
@@ -328,6 +408,30 @@ pub struct CodeBlockStyle {
     /// The syntect theme name to use.
     #[serde(default)]
     pub theme_name: Option<String>,
+
+    /// The style for line numbers.
+    #[serde(default)]
+    pub line_numbers: LineNumbersStyle,
+}
+
+/// The style for the line numbers in a piece of code.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LineNumbersStyle {
+    /// Whether to show line numbers in every code block.
+    #[serde(default)]
+    pub enabled: bool,
+
+    /// The colors to be used.
+    #[serde(default)]
+    pub colors: Colors,
+}
+
+/// The style for the output of a code block's execution.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ExecutionOutputBlockStyle {
+    /// The colors to be used.
+    #[serde(default)]
+    pub colors: Colors,
 }
 
 /// The style for inline code.
@@ -338,6 +442,205 @@ pub struct InlineCodeStyle {
     pub colors: Colors,
 }
 
+/// The style for links.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct LinkStyle {
+    /// How links are displayed.
+    #[serde(default)]
+    pub mode: LinkMode,
+}
+
+/// The way links are displayed.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkMode {
+    /// Display the link's label as a clickable hyperlink, for terminals that support it.
+    #[default]
+    Hyperlink,
+
+    /// Display the link's label followed by its URL in parenthesis.
+    LabelAndUrl,
+}
+
+/// The style for a table.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TableStyle {
+    /// The alignment.
+    #[serde(flatten, default)]
+    pub alignment: Option<Alignment>,
+
+    /// The borders to be drawn.
+    #[serde(default)]
+    pub borders: TableBorders,
+
+    /// The colors to be used for the header row.
+    #[serde(default)]
+    pub header_colors: Colors,
+
+    /// The colors to be used for the rest of the rows.
+    #[serde(default)]
+    pub row_colors: Colors,
+
+    /// The colors to be used for every other row, starting from the second one.
+    ///
+    /// This allows rows to have alternating colors, which makes long rows easier to follow.
+    #[serde(default)]
+    pub alternate_row_colors: Option<Colors>,
+}
+
+/// The borders drawn in a table.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TableBorders {
+    /// No borders at all.
+    None,
+
+    /// Only draw lines in between columns and below the header.
+    #[default]
+    Inner,
+
+    /// Draw a box around the table and lines in between columns and below the header.
+    Full,
+}
+
+/// The style for a list.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ListStyle {
+    /// The alignment.
+    #[serde(flatten, default)]
+    pub alignment: Option<Alignment>,
+
+    /// The number of columns every nesting level is indented by.
+    #[serde(default)]
+    pub indentation: Option<u16>,
+
+    /// The style for each nesting level, starting from the outermost one.
+    ///
+    /// Levels nested deeper than the ones defined here use the last one.
+    #[serde(default)]
+    pub levels: Vec<ListLevelStyle>,
+}
+
+impl ListStyle {
+    /// Get the style for the list items at the given depth.
+    pub fn level(&self, depth: u8) -> Option<&ListLevelStyle> {
+        self.levels.get(depth as usize).or(self.levels.last())
+    }
+}
+
+/// The style for the items in a specific list nesting level.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ListLevelStyle {
+    /// The bullet used for unordered list items.
+    #[serde(default)]
+    pub bullet: Option<String>,
+
+    /// The style used to number ordered list items.
+    #[serde(default)]
+    pub numbering: NumberingStyle,
+
+    /// The colors to be used for bullets and numbers.
+    #[serde(default)]
+    pub colors: Colors,
+}
+
+/// The way ordered list items are numbered.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NumberingStyle {
+    /// Arabic numbers: 1, 2, 3.
+    #[default]
+    Decimal,
+
+    /// Lowercase letters: a, b, c.
+    LowerAlpha,
+
+    /// Uppercase letters: A, B, C.
+    UpperAlpha,
+
+    /// Lowercase roman numerals: i, ii, iii.
+    LowerRoman,
+
+    /// Uppercase roman numerals: I, II, III.
+    UpperRoman,
+}
+
+// The largest number that can be written using standard roman numerals.
+const MAX_ROMAN_NUMERAL: u32 = 3999;
+
+impl NumberingStyle {
+    /// Format a list item number using this style.
+    ///
+    /// Letters and roman numerals can't represent zero so it's always displayed as a decimal number.
+    /// The same goes for numbers larger than 3999 when using roman numerals.
+    pub fn format(&self, number: u32) -> String {
+        match self {
+            _ if number == 0 => number.to_string(),
+            Self::LowerRoman | Self::UpperRoman if number > MAX_ROMAN_NUMERAL => number.to_string(),
+            Self::Decimal => number.to_string(),
+            Self::LowerAlpha => Self::alpha(number),
+            Self::UpperAlpha => Self::alpha(number).to_uppercase(),
+            Self::LowerRoman => Self::roman(number),
+            Self::UpperRoman => Self::roman(number).to_uppercase(),
+        }
+    }
+
+    // Goes a, b, ..., z, aa, ab, etc.
+    fn alpha(mut number: u32) -> String {
+        let mut letters = Vec::new();
+        while number > 0 {
+            number -= 1;
+            letters.push((b'a' + (number % 26) as u8) as char);
+            number /= 26;
+        }
+        letters.iter().rev().collect()
+    }
+
+    fn roman(mut number: u32) -> String {
+        const NUMERALS: &[(u32, &str)] = &[
+            (1000, "m"),
+            (900, "cm"),
+            (500, "d"),
+            (400, "cd"),
+            (100, "c"),
+            (90, "xc"),
+            (50, "l"),
+            (40, "xl"),
+            (10, "x"),
+            (9, "ix"),
+            (5, "v"),
+            (4, "iv"),
+            (1, "i"),
+        ];
+        let mut output = String::new();
+        for (value, numeral) in NUMERALS {
+            while number >= *value {
+                output.push_str(numeral);
+                number -= value;
+            }
+        }
+        output
+    }
+}
+
+/// The style for task list items.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct TaskListStyle {
+    /// The character used for checked items.
+    pub checked: Option<char>,
+
+    /// The character used for unchecked items.
+    pub unchecked: Option<char>,
+}
+
+/// A color palette.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub struct ColorPalette {
+    /// The colors for each class that can be referenced via `<span class="...">`.
+    #[serde(default)]
+    pub classes: BTreeMap<String, Colors>,
+}
+
 /// Vertical/horizontal padding.
 #[derive(Clone, Debug, Default, Deserialize, Serialize)]
 pub struct Padding {
@@ -369,6 +672,7 @@ pub enum ElementType {
     PresentationAuthor,
     Table,
     BlockQuote,
+    Alert,
 }
 
 /// Text colors.
@@ -406,6 +710,7 @@ pub enum LoadThemeError {
 #[cfg(test)]
 mod test {
     use super::*;
+    use rstest::rstest;
 
     #[test]
     fn validate_themes() {
@@ -413,4 +718,19 @@ mod test {
             assert!(PresentationTheme::from_name(theme_name).is_some(), "theme {theme_name} is corrupted");
         }
     }
+
+    #[rstest]
+    #[case::decimal(NumberingStyle::Decimal, 12, "12")]
+    #[case::lower_alpha(NumberingStyle::LowerAlpha, 2, "b")]
+    #[case::lower_alpha_wrapped(NumberingStyle::LowerAlpha, 28, "ab")]
+    #[case::upper_alpha(NumberingStyle::UpperAlpha, 26, "Z")]
+    #[case::lower_roman(NumberingStyle::LowerRoman, 4, "iv")]
+    #[case::upper_roman(NumberingStyle::UpperRoman, 1994, "MCMXCIV")]
+    #[case::zero(NumberingStyle::LowerRoman, 0, "0")]
+    #[case::large_decimal(NumberingStyle::Decimal, 100000, "100000")]
+    #[case::large_alpha(NumberingStyle::LowerAlpha, 100000, "eqxd")]
+    #[case::large_roman(NumberingStyle::UpperRoman, 4000, "4000")]
+    fn numbering(#[case] style: NumberingStyle, #[case] number: u32, #[case] expected: &str) {
+        assert_eq!(style.format(number), expected);
+    }
 }